@@ -0,0 +1,47 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::{app_dir, app_root, data_root};
+use std::io::Error;
+use std::panic;
+use std::path::PathBuf;
+
+/// Runs a blocking function on tokio's blocking thread pool.
+async fn blocking<F>(f: F) -> Result<PathBuf, AppDirsError>
+where
+    F: FnOnce() -> Result<PathBuf, AppDirsError> + Send + 'static,
+{
+    #[cfg(feature = "test-support")]
+    let f = crate::test_support::propagate_override(f);
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(AppDirsError::Io(Error::other(e))),
+    }
+}
+
+/// Asynchronous version of [`app_dir`](fn.app_dir.html).
+///
+/// Both resolving the path, which may look up the home directory in the
+/// passwd database, and creating the directories run on tokio's blocking
+/// thread pool, so this must be called from within a tokio runtime. Errors
+/// are the same as for the synchronous version.
+pub async fn app_dir_async(
+    t: AppDataType,
+    app: &AppInfo,
+    path: &str,
+) -> Result<PathBuf, AppDirsError> {
+    let (app, path) = (app.clone(), path.to_owned());
+    blocking(move || app_dir(t, &app, &path)).await
+}
+
+/// Asynchronous version of [`app_root`](fn.app_root.html). (See
+/// [`app_dir_async`](fn.app_dir_async.html).)
+pub async fn app_root_async(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsError> {
+    let app = app.clone();
+    blocking(move || app_root(t, &app)).await
+}
+
+/// Asynchronous version of [`data_root`](fn.data_root.html). (See
+/// [`app_dir_async`](fn.app_dir_async.html).)
+pub async fn data_root_async(t: AppDataType) -> Result<PathBuf, AppDirsError> {
+    blocking(move || data_root(t)).await
+}
@@ -0,0 +1,351 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::get_app_root;
+use crate::inventory::group_roots;
+use crate::utils::same_path;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Error, ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST: &str = "manifest.txt";
+const MANIFEST_HEADER: &str = "app_dirs2 backup 1";
+const CACHEDIR_TAG_SIGNATURE: &[u8] = b"Signature: 8a477f597d28d172789f06886806bc55";
+
+/// Settings for [`backup_app_dirs`](fn.backup_app_dirs.html).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct BackupOptions {
+    /// The data types whose app directories are archived. `UserConfig` and
+    /// `UserData` by default.
+    ///
+    /// The shared data types can be added, but their directories (e.g.
+    /// `/etc/xdg/CoolApp`) are usually only writable with elevated
+    /// privileges, so restoring such a backup as a regular user fails.
+    pub data_types: Vec<AppDataType>,
+    /// Skip directories tagged as caches with a `CACHEDIR.TAG` file (see
+    /// <https://bford.info/cachedir/>). On by default.
+    pub exclude_cache_dirs: bool,
+    /// Compress the archive with gzip. Off by default.
+    pub gzip: bool,
+}
+
+impl Default for BackupOptions {
+    fn default() -> Self {
+        BackupOptions {
+            data_types: vec![AppDataType::UserConfig, AppDataType::UserData],
+            exclude_cache_dirs: true,
+            gzip: false,
+        }
+    }
+}
+
+/// Describes the contents of a backup; stored in the archive as
+/// `manifest.txt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BackupManifest {
+    /// Name of the app the backup was made for.
+    pub app_name: String,
+    /// One entry per archived app directory.
+    pub roots: Vec<BackupRoot>,
+}
+
+/// An app directory recorded in a [`BackupManifest`](struct.BackupManifest.html).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BackupRoot {
+    /// The data types that resolved to the directory when the backup was
+    /// made. Its contents are stored in the archive below the name of the
+    /// first one (e.g. `user-config/`).
+    pub data_types: Vec<AppDataType>,
+    /// The archived files and directories, relative to the app directory and
+    /// separated by forward slashes.
+    pub paths: Vec<String>,
+}
+
+/// Writes the **app-specific** data directories of the selected data types
+/// into a tar archive, returning the manifest stored in it.
+///
+/// Regular files and directories are archived; symlinks and other special
+/// files are skipped. App directories that do not exist are left out.
+///
+/// ```no_run
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let file = std::fs::File::create("coolapp-backup.tar").unwrap();
+/// backup_app_dirs(&APP_INFO, file, &BackupOptions::default()).unwrap();
+/// ```
+pub fn backup_app_dirs<W: Write>(
+    app: &AppInfo,
+    writer: W,
+    options: &BackupOptions,
+) -> Result<BackupManifest, AppDirsError> {
+    let mut manifest = BackupManifest {
+        app_name: app.name.to_owned(),
+        roots: Vec::new(),
+    };
+    let mut sources = Vec::new();
+    for (root, data_types) in group_roots(|t| get_app_root(t, app))? {
+        if !data_types.iter().any(|t| options.data_types.contains(t)) || !root.is_dir() {
+            continue;
+        }
+        let mut paths = Vec::new();
+        collect(&root, &root, options, &mut paths)?;
+        sources.push(root);
+        manifest.roots.push(BackupRoot { data_types, paths });
+    }
+
+    if options.gzip {
+        let encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+        write_archive(encoder, &manifest, &sources)?.finish()?;
+    } else {
+        write_archive(writer, &manifest, &sources)?;
+    }
+    Ok(manifest)
+}
+
+/// Restores a backup made by [`backup_app_dirs`](fn.backup_app_dirs.html)
+/// (compressed or not) into the **app-specific** data directories on this
+/// system, returning its manifest.
+///
+/// Each archived directory is restored under the app directory of every data
+/// type it was recorded for, as resolved on this system, so the backup can
+/// be restored on a machine with a different layout. Existing files are
+/// overwritten; other files are left alone. Data types that are not supported
+/// on this system are skipped.
+///
+/// Restoring the directories of shared data types usually needs elevated
+/// privileges. Without them, the restore fails with
+/// `ErrorKind::PermissionDenied`, possibly after the directories of other
+/// data types have been written.
+///
+/// Fails with `ErrorKind::InvalidData` if the archive was not made by
+/// `backup_app_dirs` for an app of the same name.
+pub fn restore_app_dirs<R: Read>(app: &AppInfo, reader: R) -> Result<BackupManifest, AppDirsError> {
+    let mut reader = BufReader::new(reader);
+    let is_gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+    if is_gzip {
+        restore_archive(app, flate2::read::GzDecoder::new(reader))
+    } else {
+        restore_archive(app, reader)
+    }
+}
+
+fn collect(
+    root: &Path,
+    dir: &Path,
+    options: &BackupOptions,
+    paths: &mut Vec<String>,
+) -> io::Result<()> {
+    if options.exclude_cache_dirs && is_cache_dir(dir) {
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        if !file_type.is_dir() && !file_type.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let name = archive_name(relative).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("cannot archive {}", path.display()),
+            )
+        })?;
+        if file_type.is_dir() {
+            if options.exclude_cache_dirs && is_cache_dir(&path) {
+                continue;
+            }
+            paths.push(name);
+            collect(root, &path, options, paths)?;
+        } else {
+            paths.push(name);
+        }
+    }
+    Ok(())
+}
+
+fn is_cache_dir(dir: &Path) -> bool {
+    let mut signature = [0; CACHEDIR_TAG_SIGNATURE.len()];
+    fs::File::open(dir.join("CACHEDIR.TAG"))
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .is_ok_and(|()| signature == CACHEDIR_TAG_SIGNATURE)
+}
+
+/// Converts a relative path to forward-slash form, if it is valid UTF-8 and
+/// fits on a manifest line.
+fn archive_name(relative: &Path) -> Option<String> {
+    let components: Option<Vec<&str>> = relative.iter().map(|c| c.to_str()).collect();
+    let name = components?.join("/");
+    if name.contains('\n') {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+fn write_archive<W: Write>(
+    writer: W,
+    manifest: &BackupManifest,
+    sources: &[PathBuf],
+) -> Result<W, AppDirsError> {
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+
+    let manifest_text = format_manifest(manifest);
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_text.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST, manifest_text.as_bytes())?;
+
+    for (root, source) in manifest.roots.iter().zip(sources) {
+        let prefix = root.data_types[0].name();
+        builder.append_dir(prefix, source)?;
+        for path in &root.paths {
+            let full: PathBuf = source.join(path);
+            builder.append_path_with_name(&full, format!("{}/{}", prefix, path))?;
+        }
+    }
+    Ok(builder.into_inner()?)
+}
+
+fn restore_archive<R: Read>(app: &AppInfo, reader: R) -> Result<BackupManifest, AppDirsError> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = archive.entries()?;
+
+    let manifest = match entries.next() {
+        Some(entry) => {
+            let mut entry = entry?;
+            if entry.path()?.as_ref() != Path::new(MANIFEST) {
+                return Err(invalid_data("the archive has no manifest"));
+            }
+            let mut text = String::new();
+            entry.read_to_string(&mut text)?;
+            parse_manifest(&text).ok_or_else(|| invalid_data("invalid manifest"))?
+        },
+        None => return Err(invalid_data("the archive is empty")),
+    };
+    if manifest.app_name != app.name {
+        return Err(invalid_data(&format!(
+            "the backup belongs to {:?}, not {:?}",
+            manifest.app_name, app.name
+        )));
+    }
+
+    // Where to restore each archived directory on this machine.
+    let mut targets: Vec<(&str, Vec<(AppDataType, PathBuf)>)> = Vec::new();
+    for root in &manifest.roots {
+        let mut dirs: Vec<(AppDataType, PathBuf)> = Vec::new();
+        for &t in &root.data_types {
+            let dir = match get_app_root(t, app) {
+                Ok(dir) => dir,
+                Err(AppDirsError::NotSupported) => continue,
+                Err(e) => return Err(e),
+            };
+            if !dirs.iter().any(|(_, d)| same_path(d, &dir)) {
+                dirs.push((t, dir));
+            }
+        }
+        targets.push((root.data_types[0].name(), dirs));
+    }
+
+    for entry in entries {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let mut components = path.components();
+        let prefix = match components.next() {
+            Some(Component::Normal(prefix)) => prefix.to_owned(),
+            _ => return Err(invalid_data("unexpected path in archive")),
+        };
+        let relative = components.as_path().to_owned();
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(invalid_data("unexpected path in archive"));
+        }
+        let dirs = targets
+            .iter()
+            .find(|(name, _)| prefix == *name)
+            .map(|(_, dirs)| dirs)
+            .ok_or_else(|| invalid_data("unexpected path in archive"))?;
+        let entry_type = entry.header().entry_type();
+        // The entry's data can only be read once, so further copies of a
+        // file are made from the first one.
+        let mut first_copy: Option<PathBuf> = None;
+        for (_, dir) in dirs {
+            let target = dir.join(&relative);
+            let result = if entry_type.is_dir() {
+                fs::create_dir_all(&target)
+            } else if entry_type.is_file() {
+                target
+                    .parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|()| match first_copy {
+                        Some(ref first) => fs::copy(first, &target).map(drop),
+                        None => fs::File::create(&target)
+                            .and_then(|mut file| io::copy(&mut entry, &mut file))
+                            .map(drop),
+                    })
+            } else {
+                Ok(())
+            };
+            result?;
+            #[cfg(unix)]
+            if entry_type.is_file() {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = entry.header().mode()? & 0o777;
+                fs::set_permissions(&target, fs::Permissions::from_mode(mode))?;
+            }
+            first_copy.get_or_insert(target);
+        }
+    }
+    Ok(manifest)
+}
+
+fn invalid_data(msg: &str) -> AppDirsError {
+    Error::new(ErrorKind::InvalidData, msg.to_owned()).into()
+}
+
+fn format_manifest(manifest: &BackupManifest) -> String {
+    let mut text = format!("{}\napp {}\n", MANIFEST_HEADER, manifest.app_name);
+    for root in &manifest.roots {
+        let names: Vec<&str> = root.data_types.iter().map(AppDataType::name).collect();
+        text.push_str(&format!("root {}\n", names.join(" ")));
+        for path in &root.paths {
+            text.push_str(&format!("path {}\n", path));
+        }
+    }
+    text
+}
+
+fn parse_manifest(text: &str) -> Option<BackupManifest> {
+    let mut lines = text.lines();
+    if lines.next()? != MANIFEST_HEADER {
+        return None;
+    }
+    let mut manifest = BackupManifest {
+        app_name: lines.next()?.strip_prefix("app ")?.to_owned(),
+        roots: Vec::new(),
+    };
+    for line in lines {
+        if let Some(names) = line.strip_prefix("root ") {
+            let data_types = names
+                .split(' ')
+                .map(|name| name.parse().ok())
+                .collect::<Option<Vec<AppDataType>>>()?;
+            if data_types.is_empty() {
+                return None;
+            }
+            manifest.roots.push(BackupRoot {
+                data_types,
+                paths: Vec::new(),
+            });
+        } else {
+            let path = line.strip_prefix("path ")?;
+            manifest.roots.last_mut()?.paths.push(path.to_owned());
+        }
+    }
+    Some(manifest)
+}
@@ -0,0 +1,348 @@
+//! Prints the directories `app_dirs2` resolves on this system.
+//!
+//! Run `app-dirs --help` for usage.
+
+use app_dirs2::*;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const USAGE: &str = "\
+Prints the directories app_dirs2 resolves on this system.
+
+USAGE:
+    app-dirs --name <NAME> --author <AUTHOR> [OPTIONS] <TYPE> [SUBDIR]
+    app-dirs --name <NAME> --author <AUTHOR> [OPTIONS] --all
+    app-dirs --data-root [OPTIONS] (<TYPE> | --all)
+    app-dirs --name <NAME> --author <AUTHOR> --export <FORMAT> [--prefix <PREFIX>]
+    app-dirs --name <NAME> --author <AUTHOR> --doctor
+
+TYPES:
+    user-config, user-data, user-cache, shared-data, shared-config
+    (aliases: config, data, cache)
+
+OPTIONS:
+    --name <NAME>       Name of the app
+    --author <AUTHOR>   Author of the app
+    --data-root         Print top-level data directories instead of app directories
+    --all               Print the directories of every data type
+    --create            Create the directories if they do not exist
+    --json              Print the result as JSON
+    --export <FORMAT>   Print every app directory as environment variable
+                        assignments; FORMAT is one of sh, fish, powershell, dotenv
+    --prefix <PREFIX>   Prefix of the exported variable names
+                        (default: the app name in upper case, followed by `_`)
+    --doctor            Print a report explaining how each directory was resolved
+    -h, --help          Print this help
+";
+
+#[derive(Default)]
+struct Args {
+    name: Option<String>,
+    author: Option<String>,
+    data_type: Option<AppDataType>,
+    subdir: Option<String>,
+    data_root: bool,
+    all: bool,
+    create: bool,
+    json: bool,
+    export: Option<ExportFormat>,
+    prefix: Option<String>,
+    doctor: bool,
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            process::exit(2);
+        },
+    };
+    process::exit(run(&args));
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut iter: I) -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut positional = Vec::new();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            },
+            "--name" => args.name = Some(iter.next().ok_or("--name requires a value")?),
+            "--author" => args.author = Some(iter.next().ok_or("--author requires a value")?),
+            "--data-root" => args.data_root = true,
+            "--all" => args.all = true,
+            "--create" => args.create = true,
+            "--json" => args.json = true,
+            "--doctor" => args.doctor = true,
+            "--export" => {
+                let format = iter.next().ok_or("--export requires a value")?;
+                args.export = Some(parse_export_format(&format)?);
+            },
+            "--prefix" => args.prefix = Some(iter.next().ok_or("--prefix requires a value")?),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    if let Some(t) = positional.next() {
+        args.data_type = Some(
+            t.parse()
+                .map_err(|e: ParseAppDataTypeError| e.to_string())?,
+        );
+    }
+    args.subdir = positional.next();
+    if let Some(extra) = positional.next() {
+        return Err(format!("unexpected argument `{}`", extra));
+    }
+
+    if args.doctor {
+        if args.all
+            || args.data_root
+            || args.create
+            || args.json
+            || args.export.is_some()
+            || args.data_type.is_some()
+        {
+            return Err("--doctor cannot be combined with other output options".into());
+        }
+    } else if args.export.is_some() {
+        if args.all || args.data_root || args.create || args.json || args.data_type.is_some() {
+            return Err("--export cannot be combined with other output options".into());
+        }
+    } else if args.prefix.is_some() {
+        return Err("--prefix requires --export".into());
+    } else if args.all == args.data_type.is_some() {
+        return Err("expected either a data type or --all".into());
+    }
+    if args.subdir.is_some() && (args.all || args.data_root) {
+        return Err("a subdirectory can only be given for a single app directory".into());
+    }
+    if !args.data_root && (args.name.is_none() || args.author.is_none()) {
+        return Err("--name and --author are required unless --data-root is given".into());
+    }
+    Ok(args)
+}
+
+fn parse_export_format(s: &str) -> Result<ExportFormat, String> {
+    match s {
+        "sh" => Ok(ExportFormat::Sh),
+        "fish" => Ok(ExportFormat::Fish),
+        "powershell" | "pwsh" => Ok(ExportFormat::PowerShell),
+        "dotenv" | "env" => Ok(ExportFormat::Dotenv),
+        _ => Err(format!("unknown export format `{}`", s)),
+    }
+}
+
+fn run(args: &Args) -> i32 {
+    // `AppInfo` wants `&'static str`; the strings live until the process exits
+    // anyway.
+    let app = AppInfo {
+        name: leak(args.name.clone()),
+        author: leak(args.author.clone()),
+    };
+    if args.doctor {
+        print!("{}", diagnose(&app));
+        return 0;
+    }
+    if let Some(format) = args.export {
+        let prefix = args
+            .prefix
+            .clone()
+            .unwrap_or_else(|| default_prefix(app.name));
+        return match export_app_roots(&app, format, &prefix) {
+            Ok(exports) => {
+                print!("{}", exports);
+                0
+            },
+            Err(e) => {
+                eprintln!("error: {}", e);
+                1
+            },
+        };
+    }
+
+    let resolve = |t: AppDataType| -> Result<PathBuf, ContextError> {
+        match (args.data_root, &args.subdir, args.create) {
+            (true, _, false) => get_data_root_with_context(t),
+            (true, _, true) => data_root_with_context(t),
+            (false, Some(subdir), false) => get_app_dir_with_context(t, &app, subdir),
+            (false, Some(subdir), true) => app_dir_with_context(t, &app, subdir),
+            (false, None, false) => get_app_root_with_context(t, &app),
+            (false, None, true) => app_root_with_context(t, &app),
+        }
+    };
+
+    if let Some(t) = args.data_type {
+        return match resolve(t) {
+            Ok(path) => {
+                if args.json {
+                    println!("{}", json_string(&path));
+                } else {
+                    println!("{}", path.display());
+                }
+                0
+            },
+            Err(e) => {
+                eprintln!("error: {}", e);
+                1
+            },
+        };
+    }
+
+    let results: Vec<_> = AppDataType::all().map(|t| (t, resolve(t))).collect();
+    if args.json {
+        let fields: Vec<_> = results
+            .iter()
+            .map(|(t, result)| {
+                let value = match result {
+                    Ok(path) => json_string(path),
+                    Err(_) => "null".to_owned(),
+                };
+                format!("  \"{}\": {}", t, value)
+            })
+            .collect();
+        println!("{{\n{}\n}}", fields.join(",\n"));
+    } else {
+        for (t, result) in &results {
+            match result {
+                Ok(path) => println!("{}\t{}", t, path.display()),
+                Err(e) => eprintln!("{}\terror: {}", t, e),
+            }
+        }
+    }
+    if results.iter().all(|(_, result)| result.is_ok()) {
+        0
+    } else {
+        1
+    }
+}
+
+fn default_prefix(name: &str) -> String {
+    let mut prefix: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    // Variable names must not start with a digit.
+    if prefix.starts_with(|c: char| c.is_ascii_digit()) {
+        prefix.insert(0, '_');
+    }
+    prefix.push('_');
+    prefix
+}
+
+fn leak(s: Option<String>) -> &'static str {
+    Box::leak(s.unwrap_or_default().into_boxed_str())
+}
+
+/// Renders a path as a JSON string. Paths that are not valid UTF-8 are
+/// converted lossily.
+fn json_string(path: &Path) -> String {
+    let mut buf = String::from("\"");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if (c as u32) < 0x20 => buf.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|&arg| arg.to_owned()))
+    }
+
+    #[test]
+    fn test_app_dir() {
+        let args = parse(&["--name", "app", "--author", "dev", "config", "a/b"]).unwrap();
+        assert_eq!(Some("app"), args.name.as_deref());
+        assert_eq!(Some("dev"), args.author.as_deref());
+        assert_eq!(Some(AppDataType::UserConfig), args.data_type);
+        assert_eq!(Some("a/b"), args.subdir.as_deref());
+        assert!(!args.all && !args.data_root && !args.create && !args.json);
+    }
+
+    #[test]
+    fn test_data_root() {
+        let args = parse(&["--data-root", "--all", "--json", "--create"]).unwrap();
+        assert!(args.data_root && args.all && args.json && args.create);
+        assert_eq!(None, args.name);
+        assert_eq!(None, args.data_type);
+    }
+
+    #[test]
+    fn test_export() {
+        let args = parse(&[
+            "--name", "a", "--author", "b", "--export", "pwsh", "--prefix", "X_",
+        ])
+        .unwrap();
+        assert_eq!(Some(ExportFormat::PowerShell), args.export);
+        assert_eq!(Some("X_"), args.prefix.as_deref());
+    }
+
+    #[test_case(&["--name"], "--name requires a value"; "missing value")]
+    #[test_case(&["--verbose"], "unknown option `--verbose`"; "unknown option")]
+    #[test_case(&["--data-root", "nope"], "unknown app data type `nope`"; "unknown data type")]
+    #[test_case(&["--data-root", "config", "a", "b"], "unexpected argument `b`"; "extra argument")]
+    #[test_case(&["--data-root"], "expected either a data type or --all"; "no data type")]
+    #[test_case(&["--data-root", "--all", "config"], "expected either a data type or --all"; "data type and all")]
+    #[test_case(&["--data-root", "config", "sub"], "a subdirectory can only be given"; "data root subdir")]
+    #[test_case(&["--name", "a", "--author", "b", "--all", "config"], "expected either"; "all and data type")]
+    #[test_case(&["--name", "a", "config"], "--name and --author are required"; "no author")]
+    #[test_case(&["--author", "b", "--all"], "--name and --author are required"; "no name")]
+    #[test_case(&["--name", "a", "--author", "b", "--doctor", "--json"], "--doctor cannot be combined"; "doctor json")]
+    #[test_case(&["--name", "a", "--author", "b", "--doctor", "config"], "--doctor cannot be combined"; "doctor data type")]
+    #[test_case(&["--name", "a", "--author", "b", "--export", "sh", "--all"], "--export cannot be combined"; "export all")]
+    #[test_case(&["--name", "a", "--author", "b", "--export", "csh"], "unknown export format `csh`"; "export format")]
+    #[test_case(&["--name", "a", "--author", "b", "--prefix", "X_", "--all"], "--prefix requires --export"; "prefix")]
+    fn test_invalid(args: &[&str], msg: &str) {
+        let err = parse(args).err().expect("arguments should be rejected");
+        assert!(err.contains(msg), "{:?} does not contain {:?}", err, msg);
+    }
+
+    #[test_case("CoolApp", "COOLAPP_"; "plain")]
+    #[test_case("cool-app 2", "COOL_APP_2_"; "separators")]
+    #[test_case("1Password", "_1PASSWORD_"; "leading digit")]
+    fn test_default_prefix(name: &str, prefix: &str) {
+        assert_eq!(prefix, default_prefix(name));
+    }
+
+    #[test_case("/a/b", r#""/a/b""#; "plain")]
+    #[test_case(r#"/a "b""#, r#""/a \"b\"""#; "quotes")]
+    #[test_case(r"C:\Users", r#""C:\\Users""#; "backslash")]
+    #[test_case("a\nb\tc\rd", r#""a\nb\tc\rd""#; "escapes")]
+    #[test_case("a\u{1}b\u{1f}", r#""a\u0001b\u001f""#; "control characters")]
+    #[test_case("/¡Olé!", r#""/¡Olé!""#; "non-ascii")]
+    fn test_json_string(path: &str, json: &str) {
+        assert_eq!(json, json_string(Path::new(path)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_json_string_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(OsStr::from_bytes(b"/a\xffb"));
+        assert_eq!("\"/a\u{fffd}b\"", json_string(path));
+    }
+}
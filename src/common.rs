@@ -15,7 +15,11 @@
 ///
 /// The `author` property is currently only used by Windows, as macOS and *nix
 /// specifications don't require it. Make sure your `name` string is unique!
+///
+/// With the `serde` feature enabled, `AppInfo` can be serialized. To
+/// deserialize one, use [`OwnedAppInfo`](struct.OwnedAppInfo.html).
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AppInfo {
     /// Name of your app (e.g. "Hearthstone").
     pub name: &'static str,
@@ -23,6 +27,430 @@ pub struct AppInfo {
     pub author: &'static str,
 }
 
+/// Owned version of [`AppInfo`](struct.AppInfo.html), e.g. for an app
+/// identity received from another process.
+///
+/// With the `serde` feature enabled, `OwnedAppInfo` can be serialized and
+/// deserialized, in the same format as `AppInfo`.
+///
+/// The functions of this library take an `AppInfo`, whose strings are
+/// `'static`. Keep the `OwnedAppInfo` in a `static` to borrow one from it:
+///
+/// ```
+/// use app_dirs2::*;
+/// use std::sync::OnceLock;
+/// static APP_INFO: OnceLock<OwnedAppInfo> = OnceLock::new();
+/// let info = APP_INFO
+///     .get_or_init(|| OwnedAppInfo::new("CoolApp", "SuperDev"))
+///     .as_app_info();
+/// let config = get_app_root(AppDataType::UserConfig, &info);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedAppInfo {
+    /// Name of your app (e.g. "Hearthstone").
+    pub name: String,
+    /// Author of your app (e.g. "Blizzard").
+    pub author: String,
+}
+
+impl OwnedAppInfo {
+    /// Creates an `OwnedAppInfo` from a name and an author.
+    #[must_use]
+    pub fn new(name: &str, author: &str) -> OwnedAppInfo {
+        OwnedAppInfo {
+            name: name.to_owned(),
+            author: author.to_owned(),
+        }
+    }
+
+    /// Returns an `AppInfo` borrowing the strings of this `OwnedAppInfo`.
+    #[must_use]
+    pub fn as_app_info(&'static self) -> AppInfo {
+        AppInfo {
+            name: &self.name,
+            author: &self.author,
+        }
+    }
+}
+
+impl From<&AppInfo> for OwnedAppInfo {
+    fn from(app: &AppInfo) -> Self {
+        OwnedAppInfo::new(app.name, app.author)
+    }
+}
+
+impl PartialEq<AppInfo> for OwnedAppInfo {
+    fn eq(&self, other: &AppInfo) -> bool {
+        self.name == other.name && self.author == other.author
+    }
+}
+
+/// Creates an [`AppInfo`](struct.AppInfo.html) from the package being
+/// compiled, so it cannot get out of sync with `Cargo.toml`.
+///
+/// The name is taken from the `display-name` key of the
+/// `[package.metadata.app_dirs2]` table in `Cargo.toml`, or from
+/// `CARGO_PKG_NAME` if there is none. The author is the name of the first
+/// entry of `CARGO_PKG_AUTHORS` (without the e-mail address); a package
+/// without authors uses its name as the author. Either can be given
+/// explicitly instead. Like a literal `AppInfo`, the result can be used in
+/// `const` position:
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = app_info!();
+/// const RENAMED: AppInfo = app_info!(name = "CoolApp");
+/// const BOTH: AppInfo = app_info!(name = "CoolApp", author = "SuperDev");
+/// assert_eq!(APP_INFO.name, env!("CARGO_PKG_NAME"));
+/// ```
+///
+/// The metadata table is read from the package's `Cargo.toml` at compile
+/// time. It may also be given with dotted keys or as an inline table, but
+/// only single-line string values without escape sequences are understood;
+/// other values are ignored, as if the key was not set:
+///
+/// ```toml
+/// [package.metadata.app_dirs2]
+/// display-name = "Cool App"
+/// qualifier = "com.example"
+/// ```
+///
+/// The `qualifier` is not part of an `AppInfo`, since none of the platforms
+/// uses one for the app's directories; get it with
+/// [`app_qualifier!`](macro.app_qualifier.html).
+#[macro_export]
+macro_rules! app_info {
+    () => {
+        $crate::app_info!(name = $crate::__package_name!())
+    };
+    (name = $name:expr $(,)?) => {
+        $crate::AppInfo {
+            name: $name,
+            author: $crate::__first_author(env!("CARGO_PKG_AUTHORS"), env!("CARGO_PKG_NAME")),
+        }
+    };
+    (author = $author:expr $(,)?) => {
+        $crate::AppInfo {
+            name: $crate::__package_name!(),
+            author: $author,
+        }
+    };
+    (name = $name:expr, author = $author:expr $(,)?) => {
+        $crate::AppInfo {
+            name: $name,
+            author: $author,
+        }
+    };
+    (author = $author:expr, name = $name:expr $(,)?) => {
+        $crate::app_info!(name = $name, author = $author)
+    };
+}
+
+/// Returns the `qualifier` key of the `[package.metadata.app_dirs2]` table in
+/// the `Cargo.toml` of the package being compiled, as an
+/// `Option<&'static str>`. (See [`app_info!`](macro.app_info.html).)
+///
+/// The qualifier is usually a reverse domain name (e.g. `"com.example"`) for
+/// identifiers such as a macOS bundle identifier or a D-Bus name.
+///
+/// ```
+/// const QUALIFIER: Option<&str> = app_dirs2::app_qualifier!();
+/// ```
+#[macro_export]
+macro_rules! app_qualifier {
+    () => {
+        $crate::__package_metadata!("qualifier")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __package_name {
+    () => {
+        match $crate::__package_metadata!("display-name") {
+            ::core::option::Option::Some(name) => name,
+            ::core::option::Option::None => env!("CARGO_PKG_NAME"),
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __package_metadata {
+    ($key:literal) => {{
+        // Evaluated at compile time, so reading the manifest has no cost.
+        const VALUE: ::core::option::Option<&str> = $crate::__package_metadata(
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")),
+            $key,
+        );
+        VALUE
+    }};
+}
+
+/// Returns the name of the first author in a `CARGO_PKG_AUTHORS` value, or
+/// `fallback` if there is none. Used by [`app_info!`](macro.app_info.html).
+#[doc(hidden)]
+#[must_use]
+pub const fn __first_author(authors: &'static str, fallback: &'static str) -> &'static str {
+    let bytes = authors.as_bytes();
+    // The name ends at the e-mail address or the next author.
+    let mut end = 0;
+    while end < bytes.len() && bytes[end] != b'<' && bytes[end] != b':' {
+        end += 1;
+    }
+    while end > 0 && bytes[end - 1] == b' ' {
+        end -= 1;
+    }
+    if end == 0 {
+        return fallback;
+    }
+    match std::str::from_utf8(bytes.split_at(end).0) {
+        Ok(author) => author,
+        // Unreachable: the name ends before an ASCII character.
+        Err(_) => fallback,
+    }
+}
+
+/// Returns the string value of `key` in the `[package.metadata.app_dirs2]`
+/// table of a `Cargo.toml`, or `None` if it is not set or not a single-line
+/// string without escape sequences. Used by
+/// [`app_info!`](macro.app_info.html).
+#[doc(hidden)]
+#[must_use]
+pub const fn __package_metadata(manifest: &'static str, key: &'static str) -> Option<&'static str> {
+    let bytes = manifest.as_bytes();
+    let path: [&[u8]; 4] = [b"package", b"metadata", b"app_dirs2", key.as_bytes()];
+    // How many components of `path` the current table header matches.
+    let mut table = 0;
+    let mut pos = 0;
+    loop {
+        pos = skip_space(bytes, pos, true);
+        if pos == bytes.len() {
+            return None;
+        }
+        if bytes[pos] == b'[' {
+            table = NO_MATCH;
+            // Array of tables headers cannot match.
+            if pos + 1 < bytes.len() && bytes[pos + 1] != b'[' {
+                let (matched, close) = match_key(bytes, pos + 1, &path, 0);
+                if matched < path.len() && close < bytes.len() && bytes[close] == b']' {
+                    table = matched;
+                }
+            }
+        } else {
+            let (matched, eq) = match_key(bytes, pos, &path, table);
+            if eq < bytes.len() && bytes[eq] == b'=' {
+                let (found, value, end) =
+                    find_value(bytes, skip_space(bytes, eq + 1, false), &path, matched);
+                if found {
+                    return value;
+                }
+                pos = end;
+            }
+        }
+        // Whatever follows on the line is a comment or malformed.
+        while pos < bytes.len() && bytes[pos] != b'\n' {
+            pos += 1;
+        }
+    }
+}
+
+/// The number of matched key components for a key that does not match.
+const NO_MATCH: usize = usize::MAX;
+
+/// Returns the index of the first byte at or after `pos` that is not a space
+/// or tab or, if `newlines` is set, a line break or part of a comment.
+const fn skip_space(bytes: &[u8], mut pos: usize, newlines: bool) -> usize {
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b' ' | b'\t' => {},
+            b'\r' | b'\n' if newlines => {},
+            b'#' if newlines => {
+                while pos + 1 < bytes.len() && bytes[pos + 1] != b'\n' {
+                    pos += 1;
+                }
+            },
+            _ => break,
+        }
+        pos += 1;
+    }
+    pos
+}
+
+/// Parses the (possibly dotted and quoted) key at `pos`, whose components
+/// continue `path` after `matched` components, returning how many
+/// components of `path` it matches in total (or `NO_MATCH`) and the index
+/// of the first byte after it that is not a space or tab.
+const fn match_key(
+    bytes: &[u8],
+    mut pos: usize,
+    path: &[&[u8]],
+    mut matched: usize,
+) -> (usize, usize) {
+    loop {
+        pos = skip_space(bytes, pos, false);
+        let start = pos;
+        let (content_start, content_end, next, plain) = if pos < bytes.len()
+            && (bytes[pos] == b'"' || bytes[pos] == b'\'')
+        {
+            string_at(bytes, pos)
+        } else {
+            while pos < bytes.len()
+                && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'-' || bytes[pos] == b'_')
+            {
+                pos += 1;
+            }
+            (start, pos, pos, true)
+        };
+        if next == start {
+            return (NO_MATCH, pos);
+        }
+        if matched < path.len()
+            && plain
+            && is_equal(bytes, content_start, content_end, path[matched])
+        {
+            matched += 1;
+        } else {
+            matched = NO_MATCH;
+        }
+        pos = skip_space(bytes, next, false);
+        if pos == bytes.len() || bytes[pos] != b'.' {
+            return (matched, pos);
+        }
+        pos += 1;
+    }
+}
+
+/// Returns whether `bytes[start..end]` equals `token`.
+const fn is_equal(bytes: &[u8], start: usize, end: usize, token: &[u8]) -> bool {
+    if end - start != token.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < token.len() {
+        if bytes[start + i] != token[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Parses the string starting with the quote at `pos`, returning the bounds
+/// of its contents, the index after it and whether it is a single-line
+/// string without escape sequences.
+const fn string_at(bytes: &[u8], pos: usize) -> (usize, usize, usize, bool) {
+    let quote = bytes[pos];
+    let multiline = pos + 2 < bytes.len() && bytes[pos + 1] == quote && bytes[pos + 2] == quote;
+    let start = if multiline { pos + 3 } else { pos + 1 };
+    let mut plain = !multiline;
+    let mut end = start;
+    while end < bytes.len() {
+        if quote == b'"' && bytes[end] == b'\\' {
+            plain = false;
+            end += 2;
+            continue;
+        }
+        if !multiline && bytes[end] == b'\n' {
+            // Unterminated.
+            return (start, end, end, false);
+        }
+        if bytes[end] == quote
+            && (!multiline
+                || (end + 2 < bytes.len() && bytes[end + 1] == quote && bytes[end + 2] == quote))
+        {
+            let mut next = if multiline { end + 3 } else { end + 1 };
+            // Up to two quotes before the closing ones are part of the string.
+            let mut extra = 0;
+            while multiline && extra < 2 && next < bytes.len() && bytes[next] == quote {
+                end += 1;
+                next += 1;
+                extra += 1;
+            }
+            return (start, end, next, plain);
+        }
+        end += 1;
+    }
+    let end = if end > bytes.len() { bytes.len() } else { end };
+    (start, end, end, false)
+}
+
+/// Looks for `path` in the value at `pos`, whose key matches `matched`
+/// components of it. Returns whether it was found, its string value if it is
+/// readable and the index after the value.
+const fn find_value(
+    bytes: &'static [u8],
+    pos: usize,
+    path: &[&[u8]],
+    matched: usize,
+) -> (bool, Option<&'static str>, usize) {
+    if pos == bytes.len() {
+        return (matched == path.len(), None, pos);
+    }
+    if matched == path.len() {
+        if bytes[pos] != b'"' && bytes[pos] != b'\'' {
+            return (true, None, pos);
+        }
+        let (start, end, next, plain) = string_at(bytes, pos);
+        if !plain {
+            return (true, None, next);
+        }
+        return match std::str::from_utf8(bytes.split_at(end).0.split_at(start).1) {
+            Ok(value) => (true, Some(value), next),
+            Err(_) => (true, None, next),
+        };
+    }
+    match bytes[pos] {
+        b'"' | b'\'' => {
+            let (_, _, next, _) = string_at(bytes, pos);
+            (false, None, next)
+        },
+        b'[' | b'{' => {
+            let close = if bytes[pos] == b'[' { b']' } else { b'}' };
+            let mut pos = pos + 1;
+            loop {
+                pos = skip_space(bytes, pos, true);
+                if pos == bytes.len() {
+                    return (false, None, pos);
+                }
+                if bytes[pos] == close {
+                    return (false, None, pos + 1);
+                }
+                let start = pos;
+                let mut value_matched = NO_MATCH;
+                if close == b'}' {
+                    let (key_matched, eq) = match_key(bytes, pos, path, matched);
+                    if eq == bytes.len() || bytes[eq] != b'=' {
+                        return (false, None, eq);
+                    }
+                    value_matched = key_matched;
+                    pos = skip_space(bytes, eq + 1, false);
+                }
+                let (found, value, next) = find_value(bytes, pos, path, value_matched);
+                if found {
+                    return (true, value, next);
+                }
+                pos = skip_space(bytes, next, true);
+                if pos < bytes.len() && bytes[pos] == b',' {
+                    pos += 1;
+                } else if next == start || pos == bytes.len() || bytes[pos] != close {
+                    // Malformed.
+                    return (false, None, next);
+                }
+            }
+        },
+        _ => {
+            // Numbers, booleans and dates, which may contain spaces.
+            let mut pos = pos;
+            while pos < bytes.len() && !matches!(bytes[pos], b',' | b']' | b'}' | b'\n' | b'#') {
+                pos += 1;
+            }
+            (false, None, pos)
+        },
+    }
+}
+
 /// Enum specifying the type of app data you want to store.
 ///
 /// **Different platforms are NOT guaranteed to distinguish between each data
@@ -30,8 +458,18 @@ pub struct AppInfo {
 ///
 /// Example: Windows does not supported shared application data and does not
 /// distinguish between config and data. Therefore, on Windows, all variants
-/// except `UserCache` return the same path.
+/// except `UserCache` return the same path. Use
+/// [`data_type_aliases`](fn.data_type_aliases.html) to find out which data
+/// types share a directory on the running system.
+///
+/// With the `serde` feature enabled, data types are serialized by their
+/// kebab-case names (e.g. `"user-config"`).
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum AppDataType {
     /// User-specific app configuration data.
     UserConfig,
@@ -45,6 +483,14 @@ pub enum AppDataType {
     SharedConfig,
 }
 
+const ALL_DATA_TYPES: [AppDataType; 5] = [
+    AppDataType::UserConfig,
+    AppDataType::UserData,
+    AppDataType::UserCache,
+    AppDataType::SharedData,
+    AppDataType::SharedConfig,
+];
+
 impl AppDataType {
     /// Returns `true` for non-user-specific data types.
     #[must_use]
@@ -52,6 +498,122 @@ impl AppDataType {
         use crate::AppDataType::{SharedConfig, SharedData};
         matches!(self, SharedData | SharedConfig)
     }
+
+    /// Returns an iterator over every data type, in declaration order.
+    pub fn all() -> impl Iterator<Item = AppDataType> + Clone {
+        ALL_DATA_TYPES.iter().copied()
+    }
+
+    /// Returns the kebab-case name of the data type (e.g. `"user-config"`).
+    ///
+    /// This is also what `Display` prints and what `FromStr` parses.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        use crate::AppDataType::*;
+        match *self {
+            UserConfig => "user-config",
+            UserData => "user-data",
+            UserCache => "user-cache",
+            SharedData => "shared-data",
+            SharedConfig => "shared-config",
+        }
+    }
+}
+
+impl std::fmt::Display for AppDataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for AppDataType {
+    type Err = ParseAppDataTypeError;
+
+    /// Parses a data type from its name.
+    ///
+    /// Parsing is case-insensitive and treats `_` like `-`. Besides the names
+    /// returned by [`name`](#method.name), the variant names (`UserConfig`)
+    /// and the aliases `config`, `data`, `cache`, `xdg-config`, `xdg-data`
+    /// and `xdg-cache` (for the user-specific types) are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use crate::AppDataType::*;
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "user-config" | "userconfig" | "config" | "xdg-config" => Ok(UserConfig),
+            "user-data" | "userdata" | "data" | "xdg-data" => Ok(UserData),
+            "user-cache" | "usercache" | "cache" | "xdg-cache" => Ok(UserCache),
+            "shared-data" | "shareddata" => Ok(SharedData),
+            "shared-config" | "sharedconfig" => Ok(SharedConfig),
+            _ => Err(ParseAppDataTypeError {
+                input: s.to_owned(),
+            }),
+        }
+    }
+}
+
+/// Error returned when parsing an [`AppDataType`](enum.AppDataType.html)
+/// from a string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseAppDataTypeError {
+    input: String,
+}
+
+impl ParseAppDataTypeError {
+    /// Returns the string that could not be parsed.
+    #[must_use]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl std::fmt::Display for ParseAppDataTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "unknown app data type `{}` (expected one of ",
+            self.input
+        )?;
+        for (i, t) in AppDataType::all().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(t.name())?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl std::error::Error for ParseAppDataTypeError {}
+
+/// Where a platform backend took a top-level data directory from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ResolutionSource {
+    /// The directory was read from the named environment variable (e.g.
+    /// `XDG_CONFIG_HOME`).
+    EnvVar(&'static str),
+    /// The platform's default location was used (e.g. `$HOME/.config` when
+    /// `XDG_CONFIG_HOME` is not set).
+    Default,
+    /// The directory was queried from the named system API.
+    SystemApi(&'static str),
+    /// The platform's default location was used, relative to the home
+    /// directory recorded in the passwd database rather than `$HOME`.
+    Passwd,
+    /// The directory was overridden for the current thread (e.g. by
+    /// `FakeHome` from the `test-support` feature).
+    Override,
+}
+
+impl std::fmt::Display for ResolutionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match *self {
+            ResolutionSource::EnvVar(var) => write!(f, "environment variable {}", var),
+            ResolutionSource::Default => f.write_str("platform default"),
+            ResolutionSource::SystemApi(call) => write!(f, "system API {}", call),
+            ResolutionSource::Passwd => f.write_str("home directory from the passwd database"),
+            ResolutionSource::Override => f.write_str("override"),
+        }
+    }
 }
 
 const ERR_NOT_SUPPORTED: &str = "App data directories not supported";
@@ -65,11 +627,87 @@ pub enum AppDirsError {
     /// App-specific directories are not properly supported by the system
     /// (e.g. required environment variables don't exist).
     NotSupported,
-    /// App info given to this library was invalid (e.g. app name or author
-    /// were empty).
+    /// App info given to this library was invalid (e.g. app name, author,
+    /// version or profile name were empty).
     InvalidAppInfo,
 }
 
+/// Details about what failed, as carried by a
+/// [`ContextError`](struct.ContextError.html).
+///
+/// Every field is optional, as not every failure has every detail.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ErrorContext {
+    /// The data type that was being resolved.
+    pub data_type: Option<AppDataType>,
+    /// The platform backend that failed (e.g. `"xdg"`, `"windows"`,
+    /// `"android"`).
+    pub backend: Option<&'static str>,
+    /// The environment variable that was missing or unusable.
+    pub env_var: Option<&'static str>,
+    /// The system API call that failed (e.g. `"SHGetKnownFolderPath"`).
+    pub api_call: Option<&'static str>,
+    /// The path involved, e.g. the directory that could not be created.
+    pub path: Option<std::path::PathBuf>,
+}
+
+/// An [`AppDirsError`](enum.AppDirsError.html) together with details about
+/// what failed, as returned by
+/// [`get_app_root_with_context`](fn.get_app_root_with_context.html) and the
+/// other `*_with_context` functions.
+///
+/// The other functions of this crate return the bare `AppDirsError`.
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// if let Err(e) = get_app_root_with_context(AppDataType::UserConfig, &APP_INFO) {
+///     // e.g. "App data directories not supported (data type: user-config,
+///     // backend: xdg, environment variable: HOME)"
+///     eprintln!("{}", e);
+///     if let AppDirsError::NotSupported = e.error() {}
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ContextError {
+    error: AppDirsError,
+    context: ErrorContext,
+}
+
+impl ContextError {
+    /// Returns the underlying error.
+    #[must_use]
+    pub fn error(&self) -> &AppDirsError {
+        &self.error
+    }
+
+    /// Returns the details about what failed.
+    #[must_use]
+    pub fn context(&self) -> &ErrorContext {
+        &self.context
+    }
+
+    /// Returns the underlying error, discarding the details.
+    #[must_use]
+    pub fn into_error(self) -> AppDirsError {
+        self.error
+    }
+
+    /// Adds details to the error.
+    pub(crate) fn with_context<F: FnOnce(&mut ErrorContext)>(mut self, f: F) -> ContextError {
+        f(&mut self.context);
+        self
+    }
+}
+
+impl AppDirsError {
+    /// Attaches details to the error.
+    pub(crate) fn with_context<F: FnOnce(&mut ErrorContext)>(self, f: F) -> ContextError {
+        ContextError::from(self).with_context(f)
+    }
+}
+
 impl std::fmt::Display for AppDirsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         use crate::AppDirsError::*;
@@ -81,6 +719,34 @@ impl std::fmt::Display for AppDirsError {
     }
 }
 
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let mut sep = "";
+        let mut field =
+            |f: &mut std::fmt::Formatter<'_>, name: &str, value: &dyn std::fmt::Display| {
+                let result = write!(f, "{}{}: {}", sep, name, value);
+                sep = ", ";
+                result
+            };
+        if let Some(t) = self.data_type {
+            field(f, "data type", &t)?;
+        }
+        if let Some(backend) = self.backend {
+            field(f, "backend", &backend)?;
+        }
+        if let Some(var) = self.env_var {
+            field(f, "environment variable", &var)?;
+        }
+        if let Some(call) = self.api_call {
+            field(f, "API call", &call)?;
+        }
+        if let Some(ref path) = self.path {
+            field(f, "path", &path.display())?;
+        }
+        Ok(())
+    }
+}
+
 impl std::error::Error for AppDirsError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         use crate::AppDirsError::*;
@@ -92,6 +758,43 @@ impl std::error::Error for AppDirsError {
     }
 }
 
+impl std::fmt::Display for ContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        if self.context == ErrorContext::default() {
+            std::fmt::Display::fmt(&self.error, f)
+        } else {
+            write!(f, "{} ({})", self.error, self.context)
+        }
+    }
+}
+
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl From<AppDirsError> for ContextError {
+    fn from(error: AppDirsError) -> Self {
+        ContextError {
+            error,
+            context: ErrorContext::default(),
+        }
+    }
+}
+
+impl From<std::io::Error> for ContextError {
+    fn from(e: std::io::Error) -> Self {
+        AppDirsError::Io(e).into()
+    }
+}
+
+impl From<ContextError> for AppDirsError {
+    fn from(e: ContextError) -> Self {
+        e.error
+    }
+}
+
 impl From<std::io::Error> for AppDirsError {
     fn from(e: std::io::Error) -> Self {
         AppDirsError::Io(e)
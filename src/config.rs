@@ -0,0 +1,220 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::{get_app_search_path, push_relative};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// A configuration merged from the files found along the search path, as
+/// returned by [`load_toml_config`](fn.load_toml_config.html) and
+/// [`load_json_config`](fn.load_json_config.html).
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct LayeredConfig<V> {
+    /// The merged configuration; always a table (TOML) or object (JSON).
+    pub value: V,
+    /// The files that were loaded, least important first.
+    pub files: Vec<PathBuf>,
+    /// The file each value in `value` comes from, keyed by the path of keys
+    /// leading to it. Only values that are not themselves tables or objects
+    /// are listed.
+    pub provenance: BTreeMap<Vec<String>, PathBuf>,
+}
+
+impl<V> LayeredConfig<V> {
+    /// Returns the file the value at the given path of keys comes from.
+    #[must_use]
+    pub fn source_of(&self, key: &[&str]) -> Option<&Path> {
+        let key: Vec<String> = key.iter().map(|&k| k.to_owned()).collect();
+        self.provenance.get(&key).map(PathBuf::as_path)
+    }
+}
+
+/// Loads a TOML configuration file from every **app-specific** `UserConfig`
+/// directory on the search path and merges them.
+///
+/// The directories are those of
+/// [`get_app_search_path`](fn.get_app_search_path.html), so on *nix the file
+/// is looked for in every entry of `XDG_CONFIG_DIRS` (in reverse order) and
+/// then in `XDG_CONFIG_HOME`. Each file found overrides the ones before it:
+/// tables are merged key by key, all other values (including arrays) are
+/// replaced. Missing files are skipped; if there are none, the result is an
+/// empty table.
+///
+/// The `name` parameter should be a valid relative path separated by
+/// **forward slashes** (`/`). A file that cannot be parsed fails with
+/// `ErrorKind::InvalidData`.
+///
+/// ```no_run
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let config = load_toml_config(&APP_INFO, "config.toml").unwrap();
+/// if let Some(path) = config.source_of(&["ui", "theme"]) {
+///     println!("theme set in {}", path.display());
+/// }
+/// ```
+#[cfg(feature = "toml")]
+pub fn load_toml_config(
+    app: &AppInfo,
+    name: &str,
+) -> Result<LayeredConfig<toml::Value>, AppDirsError> {
+    load(app, name)
+}
+
+/// Loads a JSON configuration file from every **app-specific** `UserConfig`
+/// directory on the search path and merges them. Each file must hold an
+/// object. (See [`load_toml_config`](fn.load_toml_config.html).)
+#[cfg(feature = "json")]
+pub fn load_json_config(
+    app: &AppInfo,
+    name: &str,
+) -> Result<LayeredConfig<serde_json::Value>, AppDirsError> {
+    load(app, name)
+}
+
+/// The operations merging needs on a parsed configuration value.
+trait ConfigValue: Sized {
+    fn parse(text: &str) -> Result<Self, String>;
+    fn empty_table() -> Self;
+    fn is_table(&self) -> bool;
+    /// Takes the entries out of a table.
+    fn into_entries(self) -> Vec<(String, Self)>;
+    fn entry_mut(&mut self, key: &str) -> Option<&mut Self>;
+    fn insert(&mut self, key: String, value: Self);
+}
+
+#[cfg(feature = "toml")]
+impl ConfigValue for toml::Value {
+    fn parse(text: &str) -> Result<Self, String> {
+        text.parse::<toml::Table>()
+            .map(toml::Value::Table)
+            .map_err(|e| e.to_string())
+    }
+
+    fn empty_table() -> Self {
+        toml::Value::Table(toml::Table::new())
+    }
+
+    fn is_table(&self) -> bool {
+        self.is_table()
+    }
+
+    fn into_entries(self) -> Vec<(String, Self)> {
+        match self {
+            toml::Value::Table(table) => table.into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn entry_mut(&mut self, key: &str) -> Option<&mut Self> {
+        self.get_mut(key)
+    }
+
+    fn insert(&mut self, key: String, value: Self) {
+        if let toml::Value::Table(table) = self {
+            table.insert(key, value);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ConfigValue for serde_json::Value {
+    fn parse(text: &str) -> Result<Self, String> {
+        match serde_json::from_str(text) {
+            Ok(value @ serde_json::Value::Object(_)) => Ok(value),
+            Ok(_) => Err("expected an object".to_owned()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn empty_table() -> Self {
+        serde_json::Value::Object(serde_json::Map::new())
+    }
+
+    fn is_table(&self) -> bool {
+        self.is_object()
+    }
+
+    fn into_entries(self) -> Vec<(String, Self)> {
+        match self {
+            serde_json::Value::Object(map) => map.into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn entry_mut(&mut self, key: &str) -> Option<&mut Self> {
+        self.get_mut(key)
+    }
+
+    fn insert(&mut self, key: String, value: Self) {
+        if let serde_json::Value::Object(map) = self {
+            map.insert(key, value);
+        }
+    }
+}
+
+fn load<V: ConfigValue>(app: &AppInfo, name: &str) -> Result<LayeredConfig<V>, AppDirsError> {
+    let mut config = LayeredConfig {
+        value: V::empty_table(),
+        files: Vec::new(),
+        provenance: BTreeMap::new(),
+    };
+    for dir in get_app_search_path(AppDataType::UserConfig, app)? {
+        let path = push_relative(dir, name);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let layer = V::parse(&text).map_err(|msg| {
+            // Name the file, as parse errors only give a line and column.
+            let msg = format!("{}: {}", path.display(), msg);
+            AppDirsError::from(Error::new(ErrorKind::InvalidData, msg))
+        })?;
+        merge(&mut config, &mut Vec::new(), layer, &path);
+        config.files.push(path);
+    }
+    Ok(config)
+}
+
+/// Merges the entries of the table `layer` into the table at `key` in the
+/// configuration.
+fn merge<V: ConfigValue>(
+    config: &mut LayeredConfig<V>,
+    key: &mut Vec<String>,
+    layer: V,
+    file: &Path,
+) {
+    for (name, value) in layer.into_entries() {
+        key.push(name);
+        let both_tables = value.is_table()
+            && key
+                .iter()
+                .try_fold(&mut config.value, |v, k| v.entry_mut(k))
+                .is_some_and(|v| v.is_table());
+        if both_tables {
+            merge(config, key, value, file);
+        } else {
+            // Whatever was there before is replaced entirely.
+            config.provenance.retain(|k, _| !k.starts_with(key));
+            if value.is_table() {
+                set(config, key, V::empty_table());
+                merge(config, key, value, file);
+            } else {
+                set(config, key, value);
+                config.provenance.insert(key.clone(), file.to_owned());
+            }
+        }
+        key.pop();
+    }
+}
+
+fn set<V: ConfigValue>(config: &mut LayeredConfig<V>, key: &[String], value: V) {
+    let (last, parents) = key.split_last().expect("empty key");
+    if let Some(parent) = parents
+        .iter()
+        .try_fold(&mut config.value, |v, k| v.entry_mut(k))
+    {
+        parent.insert(last.clone(), value);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::common::{AppDataType, AppInfo, ContextError, ResolutionSource};
+use crate::imp::{env_vars, get_app_root_with_context, resolve_data_root};
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Report explaining how the directories of an app were resolved on this
+/// system, as returned by [`diagnose`](fn.diagnose.html).
+///
+/// The `Display` implementation renders a plain-text report suitable for
+/// attaching to bug reports.
+#[derive(Debug)]
+pub struct Diagnosis {
+    /// The app that was diagnosed.
+    pub app: AppInfo,
+    /// One entry per data type, in [`AppDataType::all`] order.
+    ///
+    /// [`AppDataType::all`]: enum.AppDataType.html#method.all
+    pub data_types: Vec<DataTypeDiagnosis>,
+}
+
+/// How the app directory for a single data type was resolved.
+#[derive(Debug)]
+pub struct DataTypeDiagnosis {
+    /// The data type this entry is about.
+    pub data_type: AppDataType,
+    /// The platform backend that determined the directory (or failed to).
+    pub backend: Option<&'static str>,
+    /// Where the backend took the top-level data directory from, if it
+    /// succeeded.
+    pub source: Option<ResolutionSource>,
+    /// The environment variables the backend consults for this data type and
+    /// their values at the time of diagnosis.
+    pub env_vars: Vec<(&'static str, Option<OsString>)>,
+    /// The result of
+    /// [`get_app_root_with_context`](fn.get_app_root_with_context.html).
+    pub app_root: Result<PathBuf, ContextError>,
+    /// Whether the app directory exists.
+    pub exists: bool,
+    /// Whether the app directory, or if it does not exist, its nearest
+    /// existing ancestor, is writable by the current process.
+    pub writable: bool,
+    /// Free space in bytes available to the current process on the file
+    /// system holding the app directory (or its nearest existing ancestor),
+    /// if it can be determined.
+    pub available_space: Option<u64>,
+}
+
+impl DataTypeDiagnosis {
+    /// Returns `true` if the backend fell back to the platform's default
+    /// location (e.g. the XDG defaults relative to `$HOME` or the home
+    /// directory from the passwd database).
+    #[must_use]
+    pub fn used_defaults(&self) -> bool {
+        matches!(
+            self.source,
+            Some(ResolutionSource::Default | ResolutionSource::Passwd)
+        )
+    }
+}
+
+/// Explains how each data type's **app-specific** data directory is resolved
+/// for the given app, without creating anything.
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// println!("{}", diagnose(&APP_INFO));
+/// ```
+#[must_use]
+pub fn diagnose(app: &AppInfo) -> Diagnosis {
+    let data_types = AppDataType::all()
+        .map(|t| {
+            let (backend, source) = match resolve_data_root(t) {
+                Ok(resolved) => (Some(resolved.backend), Some(resolved.source)),
+                Err(e) => (e.context().backend, None),
+            };
+            let env_vars = env_vars(t)
+                .into_iter()
+                .map(|var| (var, env::var_os(var)))
+                .collect();
+            let app_root = get_app_root_with_context(t, app);
+            let existing = app_root
+                .as_ref()
+                .ok()
+                .and_then(|path| path.ancestors().find(|p| p.is_dir()));
+            DataTypeDiagnosis {
+                data_type: t,
+                backend,
+                source,
+                env_vars,
+                exists: app_root.as_ref().is_ok_and(|path| path.is_dir()),
+                writable: existing.is_some_and(is_writable),
+                available_space: existing.and_then(available_space),
+                app_root,
+            }
+        })
+        .collect();
+    Diagnosis {
+        app: app.clone(),
+        data_types,
+    }
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "app_dirs2 {} diagnosis for {:?} by {:?} ({}/{})",
+            env!("CARGO_PKG_VERSION"),
+            self.app.name,
+            self.app.author,
+            env::consts::OS,
+            env::consts::ARCH,
+        )?;
+        for d in &self.data_types {
+            writeln!(f)?;
+            writeln!(f, "{}:", d.data_type)?;
+            writeln!(f, "  backend:   {}", d.backend.unwrap_or("unknown"))?;
+            if let Some(ref source) = d.source {
+                writeln!(f, "  source:    {}", source)?;
+            }
+            for (var, value) in &d.env_vars {
+                match value {
+                    Some(value) => writeln!(f, "  env:       {}={:?}", var, value)?,
+                    None => writeln!(f, "  env:       {} is not set", var)?,
+                }
+            }
+            match d.app_root {
+                Ok(ref path) => writeln!(f, "  app root:  {}", path.display())?,
+                Err(ref e) => writeln!(f, "  error:     {}", e)?,
+            }
+            writeln!(f, "  exists:    {}", yes_no(d.exists))?;
+            writeln!(f, "  writable:  {}", yes_no(d.writable))?;
+            if let Some(bytes) = d.available_space {
+                writeln!(f, "  available: {} bytes", bytes)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+#[cfg(unix)]
+fn c_path(path: &Path) -> Option<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::CString::new(path.as_os_str().as_bytes()).ok()
+}
+
+#[cfg(unix)]
+fn is_writable(path: &Path) -> bool {
+    c_path(path).is_some_and(|path| unsafe { libc::access(path.as_ptr(), libc::W_OK) } == 0)
+}
+
+#[cfg(not(unix))]
+fn is_writable(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| !m.permissions().readonly())
+}
+
+#[cfg(unix)]
+fn available_space(path: &Path) -> Option<u64> {
+    let path = c_path(path)?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::useless_conversion)] // field types differ between platforms
+    let bytes = u64::from(stat.f_bavail).checked_mul(u64::from(stat.f_frsize));
+    bytes
+}
+
+#[cfg(windows)]
+fn available_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available), None, None) }.ok()?;
+    Some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
@@ -0,0 +1,60 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::{get_app_search_path, push_relative};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Collects the fragments of a drop-in directory (e.g. `conf.d`) below every
+/// **app-specific** data directory on the search path of provided data type,
+/// sorted lexically by file name.
+///
+/// The directories are those of
+/// [`get_app_search_path`](fn.get_app_search_path.html). Like systemd, a
+/// fragment masks fragments of the same name in less important directories,
+/// and a fragment that is empty or a symlink to `/dev/null` disables the name
+/// altogether. Only regular files (or symlinks to them) count as fragments;
+/// missing directories are skipped.
+///
+/// The `dir` parameter should be a valid relative path separated by
+/// **forward slashes** (`/`).
+///
+/// ```no_run
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// for fragment in drop_in_fragments(AppDataType::UserConfig, &APP_INFO, "conf.d").unwrap() {
+///     println!("loading {}", fragment.display());
+/// }
+/// ```
+pub fn drop_in_fragments(
+    t: AppDataType,
+    app: &AppInfo,
+    dir: &str,
+) -> Result<Vec<PathBuf>, AppDirsError> {
+    let mut fragments: BTreeMap<OsString, PathBuf> = BTreeMap::new();
+    for root in get_app_search_path(t, app)? {
+        let path = push_relative(root, dir);
+        let entries = match fs::read_dir(&path) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let fragment = entry.path();
+            if is_masked(&fragment) || fragment.is_file() {
+                fragments.insert(entry.file_name(), fragment);
+            }
+        }
+    }
+    Ok(fragments
+        .into_values()
+        .filter(|path| !is_masked(path))
+        .collect())
+}
+
+fn is_masked(path: &Path) -> bool {
+    fs::read_link(path).is_ok_and(|target| target == Path::new("/dev/null"))
+        || fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() == 0)
+}
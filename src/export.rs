@@ -0,0 +1,233 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::get_app_root;
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::io::{Error, ErrorKind};
+
+/// Syntax used by [`export_app_roots`](fn.export_app_roots.html).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ExportFormat {
+    /// POSIX shell: `export NAME='value'`.
+    Sh,
+    /// fish: `set -gx NAME 'value'`.
+    Fish,
+    /// PowerShell: `$env:NAME = 'value'`.
+    PowerShell,
+    /// `.env` file as read by systemd's `EnvironmentFile=` and most dotenv
+    /// libraries: `NAME="value"`.
+    Dotenv,
+}
+
+/// Renders the **app-specific** data directory of every data type as
+/// environment variable assignments, one per line.
+///
+/// Variables are named `prefix` followed by the data type in upper snake case,
+/// e.g. `COOLAPP_USER_CONFIG` for a prefix of `COOLAPP_`. The prefix must be
+/// empty or a valid variable name, i.e. ASCII letters, digits and underscores
+/// not starting with a digit; otherwise an `Io` error of kind `InvalidInput`
+/// is returned.
+///
+/// Paths are quoted so that the shell reads them back byte for byte, including
+/// paths that are not valid Unicode where the target syntax allows it. If a
+/// path cannot be represented in the requested syntax (e.g. a non-UTF-8 path
+/// in a `.env` file), an `Io` error of kind `InvalidData` is returned.
+pub fn export_app_roots(
+    app: &AppInfo,
+    format: ExportFormat,
+    prefix: &str,
+) -> Result<String, AppDirsError> {
+    if !is_valid_prefix(prefix) {
+        let msg = format!("{:?} is not a valid variable name prefix", prefix);
+        return Err(Error::new(ErrorKind::InvalidInput, msg).into());
+    }
+    let mut buf = String::new();
+    for t in AppDataType::all() {
+        let path = get_app_root(t, app)?;
+        let name = format!(
+            "{}{}",
+            prefix,
+            t.name().replace('-', "_").to_ascii_uppercase()
+        );
+        let value = quote(path.as_os_str(), format)?;
+        let _ = match format {
+            ExportFormat::Sh => writeln!(buf, "export {}={}", name, value),
+            ExportFormat::Fish => writeln!(buf, "set -gx {} {}", name, value),
+            ExportFormat::PowerShell => writeln!(buf, "$env:{} = {}", name, value),
+            ExportFormat::Dotenv => writeln!(buf, "{}={}", name, value),
+        };
+    }
+    Ok(buf)
+}
+
+/// Returns whether `prefix` is empty or matches `[A-Za-z_][A-Za-z0-9_]*`,
+/// so that no syntax needs to quote the variable names.
+fn is_valid_prefix(prefix: &str) -> bool {
+    !prefix.starts_with(|c: char| c.is_ascii_digit())
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Part of an OS string: either valid Unicode text or a single code unit
+/// that is not part of any valid character.
+// Unix paths only produce `Byte` pieces and Windows paths only `Wide` ones.
+#[allow(dead_code)]
+enum Piece<'a> {
+    Text(Cow<'a, str>),
+    Byte(u8),
+    Wide(u16),
+}
+
+#[cfg(unix)]
+fn split_pieces(s: &OsStr) -> Vec<Piece<'_>> {
+    use std::os::unix::ffi::OsStrExt;
+    let mut pieces = Vec::new();
+    let mut bytes = s.as_bytes();
+    while !bytes.is_empty() {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                pieces.push(Piece::Text(text.into()));
+                break;
+            },
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                if !valid.is_empty() {
+                    pieces.push(Piece::Text(String::from_utf8_lossy(valid)));
+                }
+                let invalid = e.error_len().unwrap_or(rest.len());
+                pieces.extend(rest[..invalid].iter().map(|&b| Piece::Byte(b)));
+                bytes = &rest[invalid..];
+            },
+        }
+    }
+    pieces
+}
+
+#[cfg(windows)]
+fn split_pieces(s: &OsStr) -> Vec<Piece<'_>> {
+    use std::os::windows::ffi::OsStrExt;
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    for c in std::char::decode_utf16(s.encode_wide()) {
+        match c {
+            Ok(c) => text.push(c),
+            Err(e) => {
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(std::mem::take(&mut text).into()));
+                }
+                pieces.push(Piece::Wide(e.unpaired_surrogate()));
+            },
+        }
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text.into()));
+    }
+    pieces
+}
+
+#[cfg(not(any(unix, windows)))]
+fn split_pieces(s: &OsStr) -> Vec<Piece<'_>> {
+    match s.to_str() {
+        Some(text) => vec![Piece::Text(text.into())],
+        None => vec![Piece::Byte(0xff)],
+    }
+}
+
+fn quote(s: &OsStr, format: ExportFormat) -> Result<String, AppDirsError> {
+    let pieces = split_pieces(s);
+    let unrepresentable = || {
+        AppDirsError::Io(Error::new(
+            ErrorKind::InvalidData,
+            format!("{:?} cannot be represented in {:?} syntax", s, format),
+        ))
+    };
+    let mut buf = String::new();
+    match format {
+        ExportFormat::Sh => {
+            for piece in &pieces {
+                match piece {
+                    Piece::Text(text) => {
+                        buf.push('\'');
+                        buf.push_str(&text.replace('\'', "'\\''"));
+                        buf.push('\'');
+                    },
+                    Piece::Byte(b) => {
+                        let _ = write!(buf, "\"$(printf '\\{:03o}')\"", b);
+                    },
+                    Piece::Wide(_) => return Err(unrepresentable()),
+                }
+            }
+        },
+        ExportFormat::Fish => {
+            for piece in &pieces {
+                match piece {
+                    Piece::Text(text) => {
+                        buf.push('\'');
+                        buf.push_str(&text.replace('\\', "\\\\").replace('\'', "\\'"));
+                        buf.push('\'');
+                    },
+                    Piece::Byte(b) => {
+                        let _ = write!(buf, "\\x{:02x}", b);
+                    },
+                    Piece::Wide(_) => return Err(unrepresentable()),
+                }
+            }
+        },
+        ExportFormat::PowerShell => {
+            let mut terms = Vec::new();
+            for piece in &pieces {
+                match piece {
+                    Piece::Text(text) => {
+                        let mut term = String::from("'");
+                        for c in text.chars() {
+                            // PowerShell also accepts typographic single quotes
+                            // as string delimiters.
+                            if matches!(c, '\'' | '\u{2018}'..='\u{201b}') {
+                                term.push(c);
+                            }
+                            term.push(c);
+                        }
+                        term.push('\'');
+                        terms.push(term);
+                    },
+                    Piece::Wide(w) => terms.push(format!("[char]0x{:04X}", w)),
+                    Piece::Byte(_) => return Err(unrepresentable()),
+                }
+            }
+            // `+` takes its type from the left operand, so make sure that is
+            // always a string.
+            if !terms.first().is_some_and(|t| t.starts_with('\'')) {
+                terms.insert(0, "''".to_owned());
+            }
+            buf = terms.join(" + ");
+        },
+        ExportFormat::Dotenv => {
+            buf.push('"');
+            for piece in &pieces {
+                match piece {
+                    Piece::Text(text) => {
+                        for c in text.chars() {
+                            match c {
+                                '\\' | '"' | '$' | '`' => {
+                                    buf.push('\\');
+                                    buf.push(c);
+                                },
+                                '\n' => buf.push_str("\\n"),
+                                '\r' => buf.push_str("\\r"),
+                                '\t' => buf.push_str("\\t"),
+                                c => buf.push(c),
+                            }
+                        }
+                    },
+                    Piece::Byte(_) | Piece::Wide(_) => return Err(unrepresentable()),
+                }
+            }
+            buf.push('"');
+        },
+    }
+    if buf.is_empty() {
+        buf.push_str("''");
+    }
+    Ok(buf)
+}
@@ -1,6 +1,5 @@
-use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::common::{AppDataType, AppDirsError, AppInfo, ContextError, ResolutionSource};
 use crate::utils;
-use std::fs;
 use std::path::PathBuf;
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -35,8 +34,43 @@ mod platform {
     mod android;
     mod unix;
     pub use self::android::*;
+    pub(crate) use self::unix::{create_dir_all, data_root_for_user, xdg_search_roots};
+    pub use self::unix::{
+        detect_sandbox, detect_sandbox_in, set_unix_options, systemd_directories, unix_options,
+        Sandbox, SudoPolicy, SystemdDirectory, UnixOptions, UnixUser, UserId,
+    };
 }
 
+#[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios")))]
+pub use self::platform::{
+    detect_sandbox, detect_sandbox_in, set_unix_options, systemd_directories, unix_options,
+    Sandbox, SudoPolicy, SystemdDirectory, UnixOptions, UnixUser, UserId,
+};
+
+/// A top-level data directory as determined by a platform backend.
+pub(crate) struct Resolved {
+    pub path: PathBuf,
+    pub backend: &'static str,
+    pub source: ResolutionSource,
+}
+
+impl Resolved {
+    /// Returns the app-specific directory for this top-level directory.
+    ///
+    /// The directories systemd creates for a service already belong to the
+    /// app, so the app's name is not appended to them.
+    fn into_app_root(self, app: &AppInfo) -> PathBuf {
+        if self.backend == SYSTEMD_BACKEND {
+            self.path
+        } else {
+            push_app(self.path, app)
+        }
+    }
+}
+
+/// Backend name of the directories systemd passes to services.
+pub(crate) const SYSTEMD_BACKEND: &str = "systemd";
+
 /// Creates (if necessary) and returns path to **app-specific** data
 /// **subdirectory** for provided data type and subdirectory path.
 ///
@@ -47,11 +81,17 @@ mod platform {
 /// create the full hierarchy. Therefore, a result of `Ok` guarantees that the
 /// returned path exists.
 pub fn app_dir(t: AppDataType, app: &AppInfo, path: &str) -> Result<PathBuf, AppDirsError> {
-    let path = get_app_dir(t, app, path)?;
-    match fs::create_dir_all(&path) {
-        Ok(..) => Ok(path),
-        Err(e) => Err(e.into()),
-    }
+    app_dir_with_context(t, app, path).map_err(ContextError::into_error)
+}
+
+/// Like [`app_dir`](fn.app_dir.html), but the error says what failed.
+pub fn app_dir_with_context(
+    t: AppDataType,
+    app: &AppInfo,
+    path: &str,
+) -> Result<PathBuf, ContextError> {
+    let path = get_app_dir_with_context(t, app, path)?;
+    create_dir_all(t, path)
 }
 
 /// Returns (but **does not create**) path to **app-specific** data
@@ -64,15 +104,16 @@ pub fn app_dir(t: AppDataType, app: &AppInfo, path: &str) -> Result<PathBuf, App
 /// it DOES NOT guarantee that the directory actually exists. (See
 /// [`app_dir`](fn.app_dir.html).)
 pub fn get_app_dir(t: AppDataType, app: &AppInfo, path: &str) -> Result<PathBuf, AppDirsError> {
-    if app.author.is_empty() || app.name.is_empty() {
-        return Err(AppDirsError::InvalidAppInfo);
-    }
-    get_app_root(t, app).map(|mut root| {
-        for component in path.split('/').filter(|s| !s.is_empty()) {
-            root.push(utils::sanitized(component));
-        }
-        root
-    })
+    get_app_dir_with_context(t, app, path).map_err(ContextError::into_error)
+}
+
+/// Like [`get_app_dir`](fn.get_app_dir.html), but the error says what failed.
+pub fn get_app_dir_with_context(
+    t: AppDataType,
+    app: &AppInfo,
+    path: &str,
+) -> Result<PathBuf, ContextError> {
+    get_app_root_with_context(t, app).map(|root| push_relative(root, path))
 }
 
 /// Creates (if necessary) and returns path to **app-specific** data
@@ -82,11 +123,13 @@ pub fn get_app_dir(t: AppDataType, app: &AppInfo, path: &str) -> Result<PathBuf,
 /// create the full hierarchy. Therefore, a result of `Ok` guarantees that the
 /// returned path exists.
 pub fn app_root(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsError> {
-    let path = get_app_root(t, app)?;
-    match fs::create_dir_all(&path) {
-        Ok(..) => Ok(path),
-        Err(e) => Err(e.into()),
-    }
+    app_root_with_context(t, app).map_err(ContextError::into_error)
+}
+
+/// Like [`app_root`](fn.app_root.html), but the error says what failed.
+pub fn app_root_with_context(t: AppDataType, app: &AppInfo) -> Result<PathBuf, ContextError> {
+    let path = get_app_root_with_context(t, app)?;
+    create_dir_all(t, path)
 }
 
 /// Returns (but **does not create**) path to **app-specific** data directory
@@ -96,16 +139,33 @@ pub fn app_root(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsError>
 /// it DOES NOT guarantee that the directory actually exists. (See
 /// [`app_root`](fn.app_root.html).)
 pub fn get_app_root(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsError> {
+    get_app_root_with_context(t, app).map_err(ContextError::into_error)
+}
+
+/// Like [`get_app_root`](fn.get_app_root.html), but the error says what
+/// failed.
+pub fn get_app_root_with_context(t: AppDataType, app: &AppInfo) -> Result<PathBuf, ContextError> {
     if app.author.is_empty() || app.name.is_empty() {
-        return Err(AppDirsError::InvalidAppInfo);
+        return Err(AppDirsError::InvalidAppInfo.into());
     }
-    get_data_root(t).map(|mut root| {
-        if platform::USE_AUTHOR {
-            root.push(utils::sanitized(app.author));
-        }
-        root.push(utils::sanitized(app.name));
-        root
-    })
+    resolve_data_root(t).map(|resolved| resolved.into_app_root(app))
+}
+
+fn push_app(mut root: PathBuf, app: &AppInfo) -> PathBuf {
+    if platform::USE_AUTHOR {
+        root.push(utils::sanitized(app.author));
+    }
+    root.push(utils::sanitized(app.name));
+    root
+}
+
+/// Appends a relative path separated by forward slashes to `root`, sanitizing
+/// every component like the app name.
+pub(crate) fn push_relative(mut root: PathBuf, path: &str) -> PathBuf {
+    for component in path.split('/').filter(|s| !s.is_empty()) {
+        root.push(utils::sanitized(component));
+    }
+    root
 }
 
 /// Creates (if necessary) and returns path to **top-level** data directory
@@ -115,11 +175,13 @@ pub fn get_app_root(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsErr
 /// create the full hierarchy. Therefore, a result of `Ok` guarantees that the
 /// returned path exists.
 pub fn data_root(t: AppDataType) -> Result<PathBuf, AppDirsError> {
-    let path = platform::get_app_dir(t)?;
-    match fs::create_dir_all(&path) {
-        Ok(..) => Ok(path),
-        Err(e) => Err(e.into()),
-    }
+    data_root_with_context(t).map_err(ContextError::into_error)
+}
+
+/// Like [`data_root`](fn.data_root.html), but the error says what failed.
+pub fn data_root_with_context(t: AppDataType) -> Result<PathBuf, ContextError> {
+    let path = get_data_root_with_context(t)?;
+    create_dir_all(t, path)
 }
 
 /// Returns (but **does not create**) path to **top-level** data directory for
@@ -129,5 +191,172 @@ pub fn data_root(t: AppDataType) -> Result<PathBuf, AppDirsError> {
 /// it DOES NOT guarantee that the directory actually exists. (See
 /// [`data_root`](fn.data_root.html).)
 pub fn get_data_root(t: AppDataType) -> Result<PathBuf, AppDirsError> {
-    platform::get_app_dir(t)
+    get_data_root_with_context(t).map_err(ContextError::into_error)
+}
+
+/// Like [`get_data_root`](fn.get_data_root.html), but the error says what
+/// failed.
+pub fn get_data_root_with_context(t: AppDataType) -> Result<PathBuf, ContextError> {
+    resolve_data_root(t).map(|resolved| resolved.path)
+}
+
+/// Returns (but **does not create**) every **top-level** data directory that
+/// is searched for files of provided data type, least important first.
+///
+/// For a user-specific data type, this is the directories of the matching
+/// shared data type (if any) followed by its own; for a shared data type,
+/// only the shared directories. On *nix, the shared directories are every
+/// entry of `XDG_CONFIG_DIRS` or `XDG_DATA_DIRS`, in reverse order; on other
+/// platforms, there is only one. Directories that resolve to the same
+/// location are listed once, at their most important position.
+///
+/// ```
+/// use app_dirs2::*;
+/// let search_path = get_data_search_path(AppDataType::UserConfig).unwrap();
+/// // *nix: ["/etc/xdg", "$HOME/.config"]
+/// ```
+pub fn get_data_search_path(t: AppDataType) -> Result<Vec<PathBuf>, AppDirsError> {
+    Ok(data_search_roots(t)?
+        .into_iter()
+        .map(|resolved| resolved.path)
+        .collect())
+}
+
+fn data_search_roots(t: AppDataType) -> Result<Vec<Resolved>, AppDirsError> {
+    use crate::AppDataType::*;
+    let shared = match t {
+        UserConfig | SharedConfig => Some(SharedConfig),
+        UserData | SharedData => Some(SharedData),
+        UserCache => None,
+    };
+    let mut roots = Vec::new();
+    if let Some(shared) = shared {
+        match resolve_data_root(shared) {
+            #[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios")))]
+            Ok(ref resolved) if resolved.backend == "xdg" => roots.extend(
+                platform::xdg_search_roots(shared)
+                    .into_iter()
+                    .rev()
+                    .map(|path| Resolved {
+                        path,
+                        backend: resolved.backend,
+                        source: resolved.source.clone(),
+                    }),
+            ),
+            Ok(resolved) => roots.push(resolved),
+            Err(ref e) if !t.is_shared() && matches!(e.error(), AppDirsError::NotSupported) => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if !t.is_shared() {
+        roots.push(resolve_data_root(t).map_err(ContextError::into_error)?);
+    }
+    let mut search_path: Vec<Resolved> = Vec::with_capacity(roots.len());
+    for root in roots {
+        search_path.retain(|r| !utils::same_path(&r.path, &root.path));
+        search_path.push(root);
+    }
+    Ok(search_path)
+}
+
+/// Returns (but **does not create**) every **app-specific** data directory
+/// that is searched for files of provided data type, least important first.
+/// (See [`get_data_search_path`](fn.get_data_search_path.html).)
+pub fn get_app_search_path(t: AppDataType, app: &AppInfo) -> Result<Vec<PathBuf>, AppDirsError> {
+    if app.author.is_empty() || app.name.is_empty() {
+        return Err(AppDirsError::InvalidAppInfo);
+    }
+    Ok(data_search_roots(t)?
+        .into_iter()
+        .map(|resolved| resolved.into_app_root(app))
+        .collect())
+}
+
+pub(crate) fn resolve_data_root(t: AppDataType) -> Result<Resolved, ContextError> {
+    #[cfg(feature = "test-support")]
+    if let Some(resolved) = crate::test_support::resolve_override(t) {
+        return Ok(resolved);
+    }
+    let result = platform::resolve(t).map_err(|e| e.with_context(|c| c.data_type = Some(t)));
+    #[cfg(feature = "tracing")]
+    match result {
+        Ok(ref resolved) => tracing::debug!(
+            data_type = %t,
+            backend = resolved.backend,
+            source = %resolved.source,
+            path = %resolved.path.display(),
+            "resolved data directory"
+        ),
+        Err(ref e) => {
+            tracing::debug!(data_type = %t, error = %e, "failed to resolve data directory")
+        },
+    }
+    result
+}
+
+/// Returns the environment variables the platform backend consults for the
+/// given data type.
+pub(crate) fn env_vars(t: AppDataType) -> Vec<&'static str> {
+    platform::env_vars(t)
+}
+
+/// Returns (but **does not create**) path to **top-level** data directory for
+/// provided data type, as seen by another user.
+///
+/// The directory is derived from the user's home directory using the default
+/// XDG locations (e.g. `~/.config` for `UserConfig`). The `XDG_*` variables
+/// of the current process are ignored, as they belong to a different user.
+/// If the user has no home directory, user-specific data types fail with
+/// `AppDirsError::NotSupported`. This is meant for administrative tools
+/// running as root:
+///
+/// ```no_run
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let alice = UnixUser::lookup(UserId::Name("alice")).unwrap();
+/// let config = get_app_root_for_user(AppDataType::UserConfig, &APP_INFO, &alice);
+/// ```
+#[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios")))]
+pub fn get_data_root_for_user(t: AppDataType, user: &UnixUser) -> Result<PathBuf, AppDirsError> {
+    platform::data_root_for_user(t, user)
+}
+
+/// Returns (but **does not create**) path to **app-specific** data directory
+/// for provided data type, as seen by another user. (See
+/// [`get_data_root_for_user`](fn.get_data_root_for_user.html).)
+#[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios")))]
+pub fn get_app_root_for_user(
+    t: AppDataType,
+    app: &AppInfo,
+    user: &UnixUser,
+) -> Result<PathBuf, AppDirsError> {
+    if app.author.is_empty() || app.name.is_empty() {
+        return Err(AppDirsError::InvalidAppInfo);
+    }
+    get_data_root_for_user(t, user).map(|root| push_app(root, app))
+}
+
+pub(crate) fn create_dir_all(t: AppDataType, path: PathBuf) -> Result<PathBuf, ContextError> {
+    #[cfg(feature = "tracing")]
+    let existed = path.is_dir();
+    #[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios")))]
+    let result = platform::create_dir_all(t, &path);
+    #[cfg(not(all(unix, not(target_os = "macos"), not(target_os = "ios"))))]
+    let result = std::fs::create_dir_all(&path).map_err(ContextError::from);
+    match result {
+        Ok(..) => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                data_type = %t,
+                path = %path.display(),
+                created = !existed,
+                "ensured directory exists"
+            );
+            Ok(path)
+        },
+        Err(e) => Err(e.with_context(|c| {
+            c.data_type = Some(t);
+            c.path.get_or_insert(path);
+        })),
+    }
 }
@@ -1,8 +1,10 @@
 use crate::common::*;
+use crate::imp::Resolved;
+use crate::layout::{self, TargetPlatform};
 use std::io::{Error, ErrorKind};
 use std::path::PathBuf;
 
-pub const USE_AUTHOR: bool = false;
+pub const USE_AUTHOR: bool = TargetPlatform::Android.uses_author();
 
 impl From<jni::errors::Error> for AppDirsError {
     fn from(error: jni::errors::Error) -> Self {
@@ -11,6 +13,20 @@ impl From<jni::errors::Error> for AppDirsError {
 }
 
 fn get_jni_app_dir(
+    context: &jni::objects::JObject<'_>,
+    env: &mut jni::JNIEnv<'_>,
+    method: &'static str,
+    has_string_argument: bool,
+) -> Result<String, ContextError> {
+    call_jni_app_dir(context, env, method, has_string_argument).map_err(|e| {
+        e.with_context(|c| {
+            c.backend = Some("android");
+            c.api_call = Some(method);
+        })
+    })
+}
+
+fn call_jni_app_dir(
     context: &jni::objects::JObject<'_>,
     env: &mut jni::JNIEnv<'_>,
     method: &str,
@@ -37,32 +53,48 @@ fn get_jni_app_dir(
     Ok(path_string.into())
 }
 
-pub fn get_app_dir(t: AppDataType) -> Result<PathBuf, AppDirsError> {
+pub fn env_vars(t: AppDataType) -> Vec<&'static str> {
+    super::unix::env_vars(t)
+}
+
+pub fn resolve(t: AppDataType) -> Result<Resolved, ContextError> {
     // Issue #33: Android apps run inside Termux do not have an Android JNI
     // context, so the call to `ndk_context::android_context()` below will
     // panic. However, Termux does provide the usual `$XDG_*` environment
     // variables. So as a workaround, we check those variables first. "Regular"
     // android apps will not have them, but will have a context.
 
-    let maybe_unix = super::unix::get_app_dir(t);
+    let maybe_unix = super::unix::resolve(t);
     if maybe_unix.is_ok() {
         return maybe_unix;
     }
+    #[cfg(feature = "tracing")]
+    if let Err(ref e) = maybe_unix {
+        tracing::debug!(error = %e, "XDG lookup failed, querying the Android context");
+    }
 
+    let jni_context = |call| {
+        move |e: jni::errors::Error| {
+            AppDirsError::from(e).with_context(|c| {
+                c.backend = Some("android");
+                c.api_call = Some(call);
+            })
+        }
+    };
     let android_context = ndk_context::android_context();
-    let vm = unsafe { jni::JavaVM::from_raw(android_context.vm().cast()) }?;
-    let mut env = vm.attach_current_thread()?;
+    let vm = unsafe { jni::JavaVM::from_raw(android_context.vm().cast()) }
+        .map_err(jni_context("JavaVM::from_raw"))?;
+    let mut env = vm
+        .attach_current_thread()
+        .map_err(jni_context("AttachCurrentThread"))?;
     let context = unsafe { jni::objects::JObject::from_raw(android_context.context().cast()) };
 
-    let path_string = match t {
-        AppDataType::UserConfig => get_jni_app_dir(&context, &mut env, "getDataDir", false)?,
-        AppDataType::UserData => get_jni_app_dir(&context, &mut env, "getFilesDir", false)?,
-        AppDataType::UserCache => get_jni_app_dir(&context, &mut env, "getCacheDir", false)?,
-        AppDataType::SharedData | AppDataType::SharedConfig => {
-            get_jni_app_dir(&context, &mut env, "getExternalFilesDir", true)?
-        },
-        // AppDataType::SharedCache => get_jni_app_dir(&context, &mut env, "getExternalCacheDir", false)?,
-    };
+    let (method, has_string_argument) = layout::android_method(t);
+    let path_string = get_jni_app_dir(&context, &mut env, method, has_string_argument)?;
 
-    Ok(PathBuf::from(path_string))
+    Ok(Resolved {
+        path: PathBuf::from(path_string),
+        backend: "android",
+        source: ResolutionSource::SystemApi(method),
+    })
 }
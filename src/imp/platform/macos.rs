@@ -1,27 +1,36 @@
-use crate::common::{AppDataType, AppDirsError};
-use crate::AppDataType::*;
-use std::path::{Component, Path, PathBuf};
+use crate::common::{AppDataType, AppDirsError, ContextError, ResolutionSource};
+use crate::imp::Resolved;
+use crate::layout::{self, TargetPlatform};
+use std::path::PathBuf;
 
-pub const USE_AUTHOR: bool = false;
+pub const USE_AUTHOR: bool = TargetPlatform::MacOs.uses_author();
+
+const BACKEND: &str = "macos";
+
+pub fn env_vars(t: AppDataType) -> Vec<&'static str> {
+    if t.is_shared() {
+        vec![]
+    } else {
+        vec!["HOME"]
+    }
+}
 
 #[allow(deprecated)] // it's fine on macOS
-pub fn get_app_dir(t: AppDataType) -> Result<PathBuf, AppDirsError> {
-    let dir_base: Result<PathBuf, AppDirsError> = if t.is_shared() {
-        Ok(Path::new(&Component::RootDir).into())
+pub fn resolve(t: AppDataType) -> Result<Resolved, ContextError> {
+    let default = layout::macos_default(t);
+    let home = if default.in_home {
+        std::env::home_dir().ok_or_else(|| {
+            AppDirsError::NotSupported.with_context(|c| {
+                c.backend = Some(BACKEND);
+                c.env_var = Some("HOME");
+            })
+        })?
     } else {
-        std::env::home_dir().ok_or(AppDirsError::NotSupported)
+        PathBuf::new()
     };
-    dir_base.map(|mut path| {
-        match t {
-            UserConfig | UserData | SharedConfig | SharedData => {
-                path.push("Library");
-                path.push("Application Support");
-            },
-            UserCache => {
-                path.push("Library");
-                path.push("Caches");
-            },
-        };
-        path
+    Ok(Resolved {
+        path: default.to_path(&home),
+        backend: BACKEND,
+        source: ResolutionSource::Default,
     })
 }
@@ -1,24 +1,254 @@
 extern crate xdg;
 use self::xdg::BaseDirectories as Xdg;
 use crate::common::*;
+use crate::imp::Resolved;
+use crate::layout;
 use crate::AppDataType::*;
-use std::path::PathBuf;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
+
+mod passwd;
+mod sandbox;
+mod sudo;
+mod systemd;
+pub use self::passwd::{UnixUser, UserId};
+pub use self::sandbox::{detect_sandbox, detect_sandbox_in, Sandbox};
+pub use self::sudo::{create_dir_all, SudoPolicy};
+pub use self::systemd::{systemd_directories, SystemdDirectory};
 
 // On Android we build this module to try XDG environment variables (#33), but
 // this constant is unused and triggers a compiler warning.
 #[cfg(not(target_os = "android"))]
-pub const USE_AUTHOR: bool = false;
-
-pub fn get_app_dir(t: AppDataType) -> Result<PathBuf, AppDirsError> {
-    Xdg::new()
-        .ok()
-        .as_ref()
-        .and_then(|x| match t {
-            UserConfig => Some(x.get_config_home()),
-            UserData => Some(x.get_data_home()),
-            UserCache => Some(x.get_cache_home()),
-            SharedData => x.get_data_dirs().into_iter().next(),
-            SharedConfig => x.get_config_dirs().into_iter().next(),
+pub const USE_AUTHOR: bool = layout::TargetPlatform::Unix.uses_author();
+
+const BACKEND: &str = "xdg";
+
+/// Settings for the XDG backend used on Linux, the BSDs and other *nix
+/// systems (and tried first on Android).
+///
+/// The settings apply to the whole process. Change them with
+/// [`set_unix_options`](fn.set_unix_options.html):
+///
+/// ```
+/// let mut options = app_dirs2::unix_options();
+/// options.systemd_directories = true;
+/// app_dirs2::set_unix_options(options);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct UnixOptions {
+    /// Use the directories systemd passes to services in
+    /// `$CONFIGURATION_DIRECTORY`, `$STATE_DIRECTORY` and `$CACHE_DIRECTORY`
+    /// (see [`SystemdDirectory`](enum.SystemdDirectory.html)), if they are
+    /// set. Config types map to the configuration directory, data types to
+    /// the state directory and `UserCache` to the cache directory; if several
+    /// directories are listed, the first one is used. Types without a
+    /// matching variable fall back to the XDG directories.
+    ///
+    /// These directories already belong to the service, so each is both the
+    /// top-level and the **app-specific** data directory: with
+    /// `StateDirectory=coolapp`, [`get_app_root`](fn.get_app_root.html)
+    /// returns `/var/lib/coolapp` rather than `/var/lib/coolapp/CoolApp`.
+    ///
+    /// Off by default.
+    pub systemd_directories: bool,
+    /// When running inside Snap (see [`detect_sandbox`](fn.detect_sandbox.html)),
+    /// use the Snap-specific directories as top-level data directories:
+    /// `$SNAP_USER_DATA` for `UserConfig` and `UserData`, `$SNAP_USER_COMMON`
+    /// for `UserCache` and `$SNAP_DATA` for the shared types.
+    ///
+    /// Off by default, in which case the XDG directories are used, which
+    /// snapd points into `$SNAP_USER_DATA` by setting `$HOME`.
+    pub snap_directories: bool,
+    /// What to do when the process runs under `sudo` or as a setuid program.
+    /// See [`SudoPolicy`](enum.SudoPolicy.html).
+    ///
+    /// `SudoPolicy::Ignore` by default.
+    pub sudo_policy: SudoPolicy,
+    /// When the process runs under `sudo` or as a setuid program, change the
+    /// owner of directories created by [`app_dir`](fn.app_dir.html),
+    /// [`app_root`](fn.app_root.html) and [`data_root`](fn.data_root.html) to
+    /// the invoking user and their primary group. Only directories of the
+    /// user-specific data types are handed over; directories that already
+    /// existed, and files the program creates inside them, are left alone.
+    /// Since only root can change the owner, this has no effect on a setuid
+    /// program whose owner is not root.
+    ///
+    /// Off by default.
+    pub chown_created_dirs: bool,
+    /// If `$HOME` is unset or empty, look up the home directory of the
+    /// current user in the passwd database (`getpwuid_r`), as is common for
+    /// cron jobs and container entrypoints. When disabled, the user-specific
+    /// data types fail with `AppDirsError::NotSupported` unless the matching
+    /// `XDG_*` variable is set.
+    ///
+    /// On by default, except on Android: there, the passwd database describes
+    /// a synthesized app user (with home directory `/data`), and the
+    /// directories are resolved through the app's `Context` instead.
+    pub passwd_home_fallback: bool,
+}
+
+impl UnixOptions {
+    const DEFAULT: UnixOptions = UnixOptions {
+        systemd_directories: false,
+        snap_directories: false,
+        sudo_policy: SudoPolicy::Ignore,
+        chown_created_dirs: false,
+        passwd_home_fallback: cfg!(not(target_os = "android")),
+    };
+}
+
+impl Default for UnixOptions {
+    fn default() -> Self {
+        UnixOptions::DEFAULT
+    }
+}
+
+static OPTIONS: RwLock<UnixOptions> = RwLock::new(UnixOptions::DEFAULT);
+
+/// Returns the settings currently used by the XDG backend.
+#[must_use]
+pub fn unix_options() -> UnixOptions {
+    OPTIONS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Replaces the settings used by the XDG backend for the whole process.
+pub fn set_unix_options(options: UnixOptions) {
+    *OPTIONS.write().unwrap_or_else(PoisonError::into_inner) = options;
+}
+
+pub fn env_vars(t: AppDataType) -> Vec<&'static str> {
+    let options = unix_options();
+    let mut vars = Vec::new();
+    if options.systemd_directories {
+        vars.push(SystemdDirectory::for_data_type(t).env_var());
+    }
+    if options.snap_directories {
+        vars.push(sandbox::snap_env_var(t));
+    }
+    vars.extend_from_slice(match t {
+        UserConfig => &["HOME", "XDG_CONFIG_HOME"],
+        UserData => &["HOME", "XDG_DATA_HOME"],
+        UserCache => &["HOME", "XDG_CACHE_HOME"],
+        SharedData => &["XDG_DATA_DIRS"],
+        SharedConfig => &["XDG_CONFIG_DIRS"],
+    });
+    vars
+}
+
+pub fn resolve(t: AppDataType) -> Result<Resolved, ContextError> {
+    let options = unix_options();
+    if options.systemd_directories {
+        if let Some(resolved) = systemd::resolve(t) {
+            return Ok(resolved);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(data_type = %t, "no systemd directory, falling back");
+    }
+    if options.snap_directories {
+        if let Some(resolved) = sandbox::resolve_snap(t) {
+            return Ok(resolved);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(data_type = %t, "no snap directory, falling back");
+    }
+    if let Some(resolved) = sudo::resolve(t, options.sudo_policy)? {
+        return Ok(resolved);
+    }
+
+    let not_supported = |env_var| {
+        AppDirsError::NotSupported.with_context(|c| {
+            c.backend = Some(BACKEND);
+            c.env_var = Some(env_var);
         })
-        .ok_or(AppDirsError::NotSupported)
+    };
+    let home_missing = env::var_os("HOME").is_none_or(|home| home.is_empty());
+    if home_missing && !t.is_shared() && !has_xdg_home(t) {
+        // Decide here whether to use the passwd database, rather than leaving
+        // it to `Xdg::new()`, whose fallback depends on the Rust version and
+        // ignores `passwd_home_fallback`.
+        if !options.passwd_home_fallback {
+            return Err(not_supported("HOME"));
+        }
+        let user = UnixUser::lookup_with_context(UserId::Uid(unsafe { libc::getuid() }))?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            user = %user.name,
+            home = %user.home.display(),
+            "HOME is not set, using the home directory from the passwd database"
+        );
+        if user.home.as_os_str().is_empty() {
+            return Err(not_supported("HOME"));
+        }
+        return Ok(Resolved {
+            path: xdg_default(t, &user.home),
+            backend: BACKEND,
+            source: ResolutionSource::Passwd,
+        });
+    }
+    // `Xdg::new()` only fails if no home directory can be found.
+    let x = Xdg::new().map_err(|_| not_supported("HOME"))?;
+    let (path, env_var) = match t {
+        UserConfig => (Some(x.get_config_home()), "XDG_CONFIG_HOME"),
+        UserData => (Some(x.get_data_home()), "XDG_DATA_HOME"),
+        UserCache => (Some(x.get_cache_home()), "XDG_CACHE_HOME"),
+        SharedData => (x.get_data_dirs().into_iter().next(), "XDG_DATA_DIRS"),
+        SharedConfig => (x.get_config_dirs().into_iter().next(), "XDG_CONFIG_DIRS"),
+    };
+    let path = path.ok_or_else(|| not_supported(env_var))?;
+    // Mirror `Xdg::new()`, which ignores relative paths.
+    let from_env = env::var_os(env_var)
+        .is_some_and(|value| env::split_paths(&value).any(|p| Path::new(&p).is_absolute()));
+    Ok(Resolved {
+        path,
+        backend: BACKEND,
+        source: if from_env {
+            ResolutionSource::EnvVar(env_var)
+        } else {
+            ResolutionSource::Default
+        },
+    })
+}
+
+/// Returns whether the `XDG_*_HOME` variable for the given user-specific data
+/// type is set to an absolute path.
+fn has_xdg_home(t: AppDataType) -> bool {
+    let env_var = match t {
+        UserConfig => "XDG_CONFIG_HOME",
+        UserData => "XDG_DATA_HOME",
+        UserCache => "XDG_CACHE_HOME",
+        SharedData | SharedConfig => return false,
+    };
+    env::var_os(env_var).is_some_and(|value| Path::new(&value).is_absolute())
+}
+
+/// Returns the XDG default for the given data type, i.e. the directory used
+/// when none of the `XDG_*` variables are set.
+fn xdg_default(t: AppDataType, home: &Path) -> PathBuf {
+    layout::xdg_default(t).to_path(home)
+}
+
+/// Returns every directory listed in `XDG_DATA_DIRS` or `XDG_CONFIG_DIRS`
+/// for a shared data type, most important first.
+pub fn xdg_search_roots(t: AppDataType) -> Vec<PathBuf> {
+    let x = match Xdg::new() {
+        Ok(x) => x,
+        Err(_) => return Vec::new(),
+    };
+    match t {
+        SharedData => x.get_data_dirs(),
+        SharedConfig => x.get_config_dirs(),
+        UserConfig | UserData | UserCache => Vec::new(),
+    }
+}
+
+pub fn data_root_for_user(t: AppDataType, user: &UnixUser) -> Result<PathBuf, AppDirsError> {
+    if user.home.as_os_str().is_empty() && !t.is_shared() {
+        return Err(AppDirsError::NotSupported);
+    }
+    Ok(xdg_default(t, &user.home))
 }
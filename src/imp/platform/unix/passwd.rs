@@ -0,0 +1,142 @@
+use crate::common::{AppDirsError, ContextError};
+use std::ffi::{CStr, CString, OsStr};
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::os::raw::c_char;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::{mem, ptr};
+
+/// Identifies a user account by name or numeric user ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UserId<'a> {
+    /// Login name (e.g. `"alice"`).
+    Name(&'a str),
+    /// Numeric user ID (e.g. `1000`).
+    Uid(u32),
+}
+
+/// A user account as recorded in the passwd database.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnixUser {
+    /// Login name.
+    pub name: String,
+    /// Numeric user ID.
+    pub uid: u32,
+    /// Numeric ID of the user's primary group.
+    pub gid: u32,
+    /// Home directory.
+    pub home: PathBuf,
+}
+
+impl UnixUser {
+    /// Looks up a user in the system's passwd database (`getpwnam_r` or
+    /// `getpwuid_r`), which includes users from NSS sources such as LDAP.
+    pub fn lookup(user: UserId<'_>) -> Result<UnixUser, AppDirsError> {
+        UnixUser::lookup_with_context(user).map_err(ContextError::into_error)
+    }
+
+    pub(crate) fn lookup_with_context(user: UserId<'_>) -> Result<UnixUser, ContextError> {
+        let api_call = match user {
+            UserId::Name(_) => "getpwnam_r",
+            UserId::Uid(_) => "getpwuid_r",
+        };
+        getpw(user)
+            .and_then(|entry| entry.ok_or_else(|| not_found(user)))
+            .map_err(|e| {
+                AppDirsError::from(e).with_context(|c| {
+                    c.backend = Some("passwd");
+                    c.api_call = Some(api_call);
+                })
+            })
+    }
+
+    /// Looks up a user in a file in `/etc/passwd` format instead of the
+    /// system's passwd database.
+    pub fn lookup_in_file(user: UserId<'_>, passwd: &Path) -> Result<UnixUser, AppDirsError> {
+        fs::read(passwd)
+            .and_then(|contents| parse_passwd(&contents, user).ok_or_else(|| not_found(user)))
+            .map_err(AppDirsError::from)
+    }
+}
+
+fn not_found(user: UserId<'_>) -> Error {
+    let msg = match user {
+        UserId::Name(name) => format!("no user named {:?}", name),
+        UserId::Uid(uid) => format!("no user with uid {}", uid),
+    };
+    Error::new(ErrorKind::NotFound, msg)
+}
+
+fn parse_passwd(contents: &[u8], user: UserId<'_>) -> Option<UnixUser> {
+    contents
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty() && !line.starts_with(b"#"))
+        .filter_map(|line| {
+            let fields: Vec<&[u8]> = line.split(|&b| b == b':').collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let number = |field: &[u8]| std::str::from_utf8(field).ok()?.parse().ok();
+            Some(UnixUser {
+                name: String::from_utf8_lossy(fields[0]).into_owned(),
+                uid: number(fields[2])?,
+                gid: number(fields[3])?,
+                home: PathBuf::from(OsStr::from_bytes(fields[5])),
+            })
+        })
+        .find(|entry| match user {
+            UserId::Name(name) => entry.name == name,
+            UserId::Uid(uid) => entry.uid == uid,
+        })
+}
+
+fn getpw(user: UserId<'_>) -> Result<Option<UnixUser>, Error> {
+    let name = match user {
+        UserId::Name(name) => Some(CString::new(name)?),
+        UserId::Uid(_) => None,
+    };
+    let mut buf: Vec<c_char> = vec![0; 1024];
+    loop {
+        let mut pwd: libc::passwd = unsafe { mem::zeroed() };
+        let mut result = ptr::null_mut();
+        let rc = unsafe {
+            match user {
+                UserId::Name(_) => libc::getpwnam_r(
+                    name.as_ref().map_or(ptr::null(), |name| name.as_ptr()),
+                    &mut pwd,
+                    buf.as_mut_ptr(),
+                    buf.len(),
+                    &mut result,
+                ),
+                UserId::Uid(uid) => {
+                    libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result)
+                },
+            }
+        };
+        if rc == libc::ERANGE {
+            let len = buf.len() * 2;
+            buf.resize(len, 0);
+            continue;
+        }
+        if rc != 0 {
+            return Err(Error::from_raw_os_error(rc));
+        }
+        if result.is_null() {
+            return Ok(None);
+        }
+        // The strings point into `buf`, so copy them out before returning.
+        let (name, home) = unsafe {
+            (
+                CStr::from_ptr(pwd.pw_name).to_string_lossy().into_owned(),
+                PathBuf::from(OsStr::from_bytes(CStr::from_ptr(pwd.pw_dir).to_bytes())),
+            )
+        };
+        return Ok(Some(UnixUser {
+            name,
+            uid: pwd.pw_uid,
+            gid: pwd.pw_gid,
+            home,
+        }));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::common::{AppDataType, ResolutionSource};
+use crate::imp::Resolved;
+use crate::AppDataType::*;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Application sandbox the current process runs in.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Sandbox {
+    /// Flatpak. Inside Flatpak, the `XDG_*` variables already point into
+    /// `~/.var/app/<app id>/`, so the regular XDG lookup gives the right
+    /// directories.
+    Flatpak {
+        /// The Flatpak application ID (e.g. `org.example.CoolApp`), if known.
+        app_id: Option<String>,
+    },
+    /// Snap. See
+    /// [`UnixOptions::snap_directories`](struct.UnixOptions.html#structfield.snap_directories)
+    /// for using the Snap-specific directories.
+    Snap {
+        /// The name of the snap (`$SNAP_NAME`), if known.
+        name: Option<String>,
+    },
+}
+
+/// Detects whether the current process runs inside a Flatpak or Snap
+/// sandbox.
+///
+/// Flatpak is recognized by `$FLATPAK_ID` or the `/.flatpak-info` file, Snap
+/// by `$SNAP`.
+#[must_use]
+pub fn detect_sandbox() -> Option<Sandbox> {
+    detect_sandbox_in(Path::new("/"), |var| env::var_os(var))
+}
+
+/// Same as [`detect_sandbox`](fn.detect_sandbox.html), but looks up
+/// environment variables with `env_var` and files relative to `root` instead
+/// of `/`.
+///
+/// This makes it possible to test code paths for sandboxes without running in
+/// one.
+pub fn detect_sandbox_in<F>(root: &Path, env_var: F) -> Option<Sandbox>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    let non_empty = |var| {
+        env_var(var)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string_lossy().into_owned())
+    };
+
+    let flatpak_info = root.join(".flatpak-info");
+    if let Some(app_id) = non_empty("FLATPAK_ID") {
+        return Some(Sandbox::Flatpak {
+            app_id: Some(app_id),
+        });
+    }
+    if flatpak_info.is_file() {
+        return Some(Sandbox::Flatpak {
+            app_id: flatpak_app_id(&flatpak_info),
+        });
+    }
+    if non_empty("SNAP").is_some() {
+        return Some(Sandbox::Snap {
+            name: non_empty("SNAP_NAME"),
+        });
+    }
+    None
+}
+
+/// Reads `name` from the `[Application]` group of a `.flatpak-info` file.
+fn flatpak_app_id(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let mut in_application = false;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_application = line == "[Application]";
+        } else if in_application {
+            if let Some(name) = line.strip_prefix("name=") {
+                return Some(name.trim().to_owned());
+            }
+        }
+    }
+    None
+}
+
+/// Returns the Snap environment variable the given data type maps onto.
+pub(super) fn snap_env_var(t: AppDataType) -> &'static str {
+    match t {
+        UserConfig | UserData => "SNAP_USER_DATA",
+        UserCache => "SNAP_USER_COMMON",
+        SharedData | SharedConfig => "SNAP_DATA",
+    }
+}
+
+pub(super) fn resolve_snap(t: AppDataType) -> Option<Resolved> {
+    if !matches!(detect_sandbox(), Some(Sandbox::Snap { .. })) {
+        return None;
+    }
+    let env_var = snap_env_var(t);
+    env::var_os(env_var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .map(|path| Resolved {
+            path,
+            backend: "snap",
+            source: ResolutionSource::EnvVar(env_var),
+        })
+}
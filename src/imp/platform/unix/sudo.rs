@@ -0,0 +1,198 @@
+use super::passwd::{UnixUser, UserId};
+use crate::common::{AppDataType, AppDirsError, ContextError, ResolutionSource};
+use crate::imp::Resolved;
+use std::env;
+use std::ffi::{CString, OsStr};
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::Path;
+
+/// What the XDG backend does when the process runs with elevated privileges,
+/// i.e. under `sudo` (`$SUDO_UID` or `$SUDO_USER` is set and the process runs
+/// as root) or as a setuid program (the real and effective user IDs differ).
+///
+/// `sudo` usually keeps `$HOME` (and any `XDG_*` variables) of the invoking
+/// user, so by default a program run with `sudo` writes root-owned files into
+/// the invoking user's home directory.
+///
+/// The policy only applies to the user-specific data types; `SharedData` and
+/// `SharedConfig` are resolved as usual.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SudoPolicy {
+    /// Resolve directories from `$HOME` and the `XDG_*` variables as usual.
+    /// This is the default.
+    Ignore,
+    /// Resolve directories relative to the home directory of the effective
+    /// user (usually root) from the passwd database.
+    EffectiveUser,
+    /// Resolve directories relative to the home directory of the user who
+    /// invoked `sudo` or the setuid program, from the passwd database.
+    InvokingUser,
+    /// Fail with `AppDirsError::NotSupported`.
+    Error,
+}
+
+/// Returns the user who invoked `sudo` or a setuid program, or `None` if the
+/// process does not run with elevated privileges.
+pub(super) fn invoking_user() -> Result<Option<UnixUser>, ContextError> {
+    let (uid, euid) = unsafe { (libc::getuid(), libc::geteuid()) };
+    if uid != euid {
+        return UnixUser::lookup_with_context(UserId::Uid(uid)).map(Some);
+    }
+    if euid != 0 {
+        // `$SUDO_*` are only meaningful if `sudo` actually made us root.
+        return Ok(None);
+    }
+    let non_empty = |var| env::var(var).ok().filter(|value| !value.is_empty());
+    if let Some(value) = non_empty("SUDO_UID") {
+        return match value.parse() {
+            Ok(0) => Ok(None),
+            Ok(uid) => UnixUser::lookup_with_context(UserId::Uid(uid)).map(Some),
+            Err(_) => Err(AppDirsError::NotSupported.with_context(|c| {
+                c.backend = Some("sudo");
+                c.env_var = Some("SUDO_UID");
+            })),
+        };
+    }
+    match non_empty("SUDO_USER") {
+        Some(ref name) if name != "root" => {
+            UnixUser::lookup_with_context(UserId::Name(name)).map(Some)
+        },
+        _ => Ok(None),
+    }
+}
+
+pub(super) fn resolve(
+    t: AppDataType,
+    policy: SudoPolicy,
+) -> Result<Option<Resolved>, ContextError> {
+    if policy == SudoPolicy::Ignore || t.is_shared() {
+        return Ok(None);
+    }
+    let invoking = match invoking_user()? {
+        Some(user) => user,
+        None => return Ok(None),
+    };
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        invoking_user = %invoking.name,
+        policy = ?policy,
+        "running with elevated privileges"
+    );
+    let user = match policy {
+        SudoPolicy::Ignore => unreachable!("handled above"),
+        SudoPolicy::EffectiveUser => {
+            UnixUser::lookup_with_context(UserId::Uid(unsafe { libc::geteuid() }))?
+        },
+        SudoPolicy::InvokingUser => invoking,
+        SudoPolicy::Error => {
+            return Err(AppDirsError::NotSupported.with_context(|c| c.backend = Some("sudo")));
+        },
+    };
+    Ok(Some(Resolved {
+        path: super::xdg_default(t, &user.home),
+        backend: "sudo",
+        source: ResolutionSource::Passwd,
+    }))
+}
+
+/// Like `fs::create_dir_all`, but if
+/// [`UnixOptions::chown_created_dirs`](struct.UnixOptions.html#structfield.chown_created_dirs)
+/// is set, the data type is user-specific and the process runs with elevated
+/// privileges, hands the directories it creates over to the invoking user.
+///
+/// Only root can change the owner of a directory, so a setuid program of
+/// another user creates directories as usual.
+pub fn create_dir_all(t: AppDataType, path: &Path) -> Result<(), ContextError> {
+    let is_root = unsafe { libc::geteuid() } == 0;
+    if !super::unix_options().chown_created_dirs || t.is_shared() || !is_root {
+        return fs::create_dir_all(path).map_err(ContextError::from);
+    }
+    let user = match invoking_user()? {
+        Some(user) => user,
+        None => return fs::create_dir_all(path).map_err(ContextError::from),
+    };
+    let existing = path
+        .ancestors()
+        .find(|p| p.as_os_str().is_empty() || p.exists())
+        .unwrap_or(path);
+    let missing = path.strip_prefix(existing).unwrap_or(Path::new(""));
+    let existing = if existing.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        existing
+    };
+    let mut dir = open_dir(None, existing.as_os_str())
+        .map_err(|e| AppDirsError::from(e).with_context(|c| c.path = Some(existing.to_owned())))?;
+    let mut current = existing.to_owned();
+    for name in missing.iter() {
+        current.push(name);
+        let created = make_dir_at(&dir, name)
+            .and_then(|created| {
+                // Open (and chown) through the parent without following
+                // symlinks, so the invoking user cannot swap in a symlink
+                // to a file of their choosing after the directory was made.
+                dir = open_dir(Some(&dir), name)?;
+                Ok(created)
+            })
+            .and_then(|created| {
+                if created {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(path = %current.display(), user = %user.name, "changing owner");
+                    fchown(&dir, &user)?;
+                }
+                Ok(())
+            });
+        created
+            .map_err(|e| AppDirsError::from(e).with_context(|c| c.path = Some(current.clone())))?;
+    }
+    Ok(())
+}
+
+fn c_name(name: &OsStr) -> io::Result<CString> {
+    CString::new(name.as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+/// Opens a directory. Relative to `parent`, a final symlink is not followed;
+/// without one, the directory existed already and is opened like
+/// `fs::create_dir_all` would (e.g. a `~/.config` symlinked by a dotfile
+/// manager).
+fn open_dir(parent: Option<&OwnedFd>, name: &OsStr) -> io::Result<OwnedFd> {
+    let name = c_name(name)?;
+    let flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC;
+    let fd = match parent {
+        Some(parent) => unsafe {
+            libc::openat(parent.as_raw_fd(), name.as_ptr(), flags | libc::O_NOFOLLOW)
+        },
+        None => unsafe { libc::open(name.as_ptr(), flags) },
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Creates a directory relative to `parent`, returning whether it was
+/// created (rather than already existing).
+fn make_dir_at(parent: &OwnedFd, name: &OsStr) -> io::Result<bool> {
+    let name = c_name(name)?;
+    if unsafe { libc::mkdirat(parent.as_raw_fd(), name.as_ptr(), 0o777) } == 0 {
+        return Ok(true);
+    }
+    let e = io::Error::last_os_error();
+    if e.kind() == io::ErrorKind::AlreadyExists {
+        Ok(false)
+    } else {
+        Err(e)
+    }
+}
+
+fn fchown(dir: &OwnedFd, user: &UnixUser) -> io::Result<()> {
+    if unsafe { libc::fchown(dir.as_raw_fd(), user.uid, user.gid) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
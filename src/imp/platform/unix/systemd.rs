@@ -0,0 +1,79 @@
+use crate::common::{AppDataType, ResolutionSource};
+use crate::imp::{Resolved, SYSTEMD_BACKEND};
+use crate::AppDataType::*;
+use std::env;
+use std::path::PathBuf;
+
+/// Kind of directory systemd sets up for a service.
+///
+/// When a unit sets `StateDirectory=`, `CacheDirectory=` and so on, systemd
+/// creates the directories and passes their absolute paths to the service in
+/// the corresponding environment variable.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SystemdDirectory {
+    /// `RuntimeDirectory=`, passed as `$RUNTIME_DIRECTORY`.
+    Runtime,
+    /// `StateDirectory=`, passed as `$STATE_DIRECTORY`.
+    State,
+    /// `CacheDirectory=`, passed as `$CACHE_DIRECTORY`.
+    Cache,
+    /// `LogsDirectory=`, passed as `$LOGS_DIRECTORY`.
+    Logs,
+    /// `ConfigurationDirectory=`, passed as `$CONFIGURATION_DIRECTORY`.
+    Configuration,
+}
+
+impl SystemdDirectory {
+    /// Returns the environment variable systemd passes this directory in.
+    #[must_use]
+    pub fn env_var(self) -> &'static str {
+        match self {
+            SystemdDirectory::Runtime => "RUNTIME_DIRECTORY",
+            SystemdDirectory::State => "STATE_DIRECTORY",
+            SystemdDirectory::Cache => "CACHE_DIRECTORY",
+            SystemdDirectory::Logs => "LOGS_DIRECTORY",
+            SystemdDirectory::Configuration => "CONFIGURATION_DIRECTORY",
+        }
+    }
+
+    /// Returns the kind of systemd directory that
+    /// [`UnixOptions::systemd_directories`](struct.UnixOptions.html#structfield.systemd_directories)
+    /// maps the given data type onto.
+    #[must_use]
+    pub fn for_data_type(t: AppDataType) -> SystemdDirectory {
+        match t {
+            UserConfig | SharedConfig => SystemdDirectory::Configuration,
+            UserData | SharedData => SystemdDirectory::State,
+            UserCache => SystemdDirectory::Cache,
+        }
+    }
+}
+
+/// Returns the directories systemd passed to this process for the given kind,
+/// in the order they were listed in the unit file.
+///
+/// A unit may list several directories (e.g. `StateDirectory=foo bar`), in
+/// which case systemd separates them with colons. Returns an empty list if
+/// the process was not started by systemd with that setting.
+#[must_use]
+pub fn systemd_directories(kind: SystemdDirectory) -> Vec<PathBuf> {
+    env::var_os(kind.env_var())
+        .map(|value| {
+            env::split_paths(&value)
+                .filter(|p| p.is_absolute())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(super) fn resolve(t: AppDataType) -> Option<Resolved> {
+    let kind = SystemdDirectory::for_data_type(t);
+    systemd_directories(kind)
+        .into_iter()
+        .next()
+        .map(|path| Resolved {
+            path,
+            backend: SYSTEMD_BACKEND,
+            source: ResolutionSource::EnvVar(kind.env_var()),
+        })
+}
@@ -1,9 +1,12 @@
 use crate::common::*;
-use crate::AppDataType::*;
-use std::path::PathBuf;
+use crate::imp::Resolved;
 
 pub const USE_AUTHOR: bool = false;
 
-pub fn get_app_dir(_t: AppDataType) -> Result<PathBuf, AppDirsError> {
-    Err(AppDirsError::NotSupported)
+pub fn env_vars(_t: AppDataType) -> Vec<&'static str> {
+    vec![]
+}
+
+pub fn resolve(_t: AppDataType) -> Result<Resolved, ContextError> {
+    Err(AppDirsError::NotSupported.with_context(|c| c.backend = Some("unknown")))
 }
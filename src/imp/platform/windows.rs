@@ -18,20 +18,30 @@ use windows::Win32::System::Com::CoTaskMemFree;
 use windows::Win32::UI::Shell::*;
 use windows::core::{ PWSTR, GUID };
 use crate::common::*;
-use crate::AppDataType::*;
+use crate::layout::{KnownFolder, TargetPlatform};
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
-use std::path::PathBuf;
+use crate::imp::Resolved;
 
-pub const USE_AUTHOR: bool = true;
+pub const USE_AUTHOR: bool = TargetPlatform::Windows.uses_author();
 
-pub fn get_app_dir(t: AppDataType) -> Result<PathBuf, AppDirsError> {
-    let folder_id = match t {
-        UserConfig => &FOLDERID_RoamingAppData,
-        SharedConfig | SharedData => &FOLDERID_ProgramData,
-        UserCache | UserData => &FOLDERID_LocalAppData,
+pub fn env_vars(_t: AppDataType) -> Vec<&'static str> {
+    vec![]
+}
+
+pub fn resolve(t: AppDataType) -> Result<Resolved, ContextError> {
+    let folder = KnownFolder::for_data_type(t);
+    let folder_id = match folder {
+        KnownFolder::RoamingAppData => &FOLDERID_RoamingAppData,
+        KnownFolder::ProgramData => &FOLDERID_ProgramData,
+        KnownFolder::LocalAppData => &FOLDERID_LocalAppData,
     };
-    get_folder_path(folder_id).map(|os_str| os_str.into())
+    let api_call = folder.api_call();
+    get_folder_path(folder_id).map(|os_str| Resolved {
+        path: os_str.into(),
+        backend: "windows",
+        source: ResolutionSource::SystemApi(api_call),
+    })
 }
 
 /// Wrapper around `windows::core::PWSTR` to automatically free the string pointer.
@@ -44,7 +54,7 @@ impl Drop for SafePwstr {
     }
 }
 
-fn get_folder_path(folder_id: &GUID) -> Result<OsString, AppDirsError> {
+fn get_folder_path(folder_id: &GUID) -> Result<OsString, ContextError> {
     unsafe {
         // SHGetKnownFolderPath arguments:
         // 1. reference to KNOWNFOLDERID
@@ -60,8 +70,14 @@ fn get_folder_path(folder_id: &GUID) -> Result<OsString, AppDirsError> {
                 Ok(OsStringExt::from_wide(raw_path.as_wide()))
                 // _cleanup is deallocated, so raw_path is freed
             },
-            Err(_) => {
-                Err(AppDirsError::NotSupported)
+            #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+            Err(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(error = %error, "SHGetKnownFolderPath failed");
+                Err(AppDirsError::NotSupported.with_context(|c| {
+                    c.backend = Some("windows");
+                    c.api_call = Some("SHGetKnownFolderPath");
+                }))
             }
         }
     }
@@ -0,0 +1,173 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::{get_app_root, get_data_root};
+use crate::utils::{is_below, same_path};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// One directory that belongs to an app, as returned by
+/// [`app_inventory`](fn.app_inventory.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppDirEntry {
+    /// The **app-specific** data directory.
+    pub path: PathBuf,
+    /// Every data type that resolves to this directory, in
+    /// [`AppDataType::all`](enum.AppDataType.html#method.all) order.
+    pub data_types: Vec<AppDataType>,
+    /// Whether the directory exists.
+    pub exists: bool,
+    /// Total size in bytes of the regular files below the directory.
+    pub size: u64,
+    /// Number of regular files below the directory.
+    pub file_count: u64,
+}
+
+/// Returns every **app-specific** data directory of the app, whether it
+/// exists, and how much it holds, e.g. for a "Storage" settings page.
+///
+/// Data types that resolve to the same directory on this system (see
+/// [`AppDataType`](enum.AppDataType.html)) share one entry, so nothing is
+/// counted twice. Data types that are not supported on this system are left
+/// out. Symlinks below the directories are not followed.
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// for entry in app_inventory(&APP_INFO).unwrap() {
+///     println!("{}: {} bytes in {} files", entry.path.display(), entry.size, entry.file_count);
+/// }
+/// ```
+pub fn app_inventory(app: &AppInfo) -> Result<Vec<AppDirEntry>, AppDirsError> {
+    let roots = group_roots(|t| get_app_root(t, app))?;
+    let mut entries = Vec::with_capacity(roots.len());
+    for (path, data_types) in roots {
+        let mut entry = AppDirEntry {
+            exists: path.is_dir(),
+            path,
+            data_types,
+            size: 0,
+            file_count: 0,
+        };
+        if entry.exists {
+            tally(&entry.path, &mut entry.size, &mut entry.file_count)?;
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Returns the groups of data types that resolve to the same **top-level**
+/// data directory on this system, in
+/// [`AppDataType::all`](enum.AppDataType.html#method.all) order. Data types
+/// with a directory of their own are left out, even if it lies within
+/// another data type's directory (see
+/// [`nested_data_types`](fn.nested_data_types.html)).
+///
+/// Typical results are `[[UserConfig, UserData], [SharedData, SharedConfig]]`
+/// on macOS and `[[UserData, UserCache], [SharedData, SharedConfig]]` on
+/// Windows; with the XDG defaults, every data type has its own directory.
+///
+/// ```
+/// use app_dirs2::*;
+/// let aliases = data_type_aliases().unwrap();
+/// if aliases.iter().any(|group| group.contains(&AppDataType::UserCache)) {
+///     // Clearing the cache directory would delete other data.
+/// }
+/// ```
+pub fn data_type_aliases() -> Result<Vec<Vec<AppDataType>>, AppDirsError> {
+    Ok(group_roots(get_data_root)?
+        .into_iter()
+        .map(|(_, data_types)| data_types)
+        .filter(|data_types| data_types.len() > 1)
+        .collect())
+}
+
+/// Returns the pairs of data types whose **top-level** data directories are
+/// nested on this system, as `(outer, inner)`: the directory of `inner` lies
+/// below the directory of `outer`, so deleting the latter deletes the former,
+/// too. Pairs are in [`AppDataType::all`](enum.AppDataType.html#method.all)
+/// order of `outer`, then `inner`.
+///
+/// This complements [`data_type_aliases`](fn.data_type_aliases.html), which
+/// only reports directories that are the same. For example, the cache
+/// directory of an Android app lies within its data directory, and on *nix
+/// `XDG_CACHE_HOME` may be set to a directory below `XDG_CONFIG_HOME`.
+///
+/// ```
+/// use app_dirs2::*;
+/// let nested = nested_data_types().unwrap();
+/// if nested.contains(&(AppDataType::UserCache, AppDataType::UserConfig)) {
+///     // Clearing the cache directory would delete the configuration.
+/// }
+/// ```
+pub fn nested_data_types() -> Result<Vec<(AppDataType, AppDataType)>, AppDirsError> {
+    let mut roots = Vec::new();
+    for t in AppDataType::all() {
+        match get_data_root(t) {
+            Ok(root) => roots.push((t, root)),
+            Err(AppDirsError::NotSupported) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    let mut nested = Vec::new();
+    for (outer, outer_root) in &roots {
+        for (inner, inner_root) in &roots {
+            if is_below(inner_root, outer_root) {
+                nested.push((*outer, *inner));
+            }
+        }
+    }
+    Ok(nested)
+}
+
+/// Returns the other data types that resolve to the same **top-level** data
+/// directory as the given one on this system. (See
+/// [`data_type_aliases`](fn.data_type_aliases.html).)
+pub fn aliases_of(t: AppDataType) -> Result<Vec<AppDataType>, AppDirsError> {
+    Ok(data_type_aliases()?
+        .into_iter()
+        .find(|data_types| data_types.contains(&t))
+        .map(|data_types| data_types.into_iter().filter(|&other| other != t).collect())
+        .unwrap_or_default())
+}
+
+/// Resolves a directory for every data type and groups the data types that
+/// share one. Unsupported data types are skipped.
+pub(crate) fn group_roots<F>(root: F) -> Result<Vec<(PathBuf, Vec<AppDataType>)>, AppDirsError>
+where
+    F: Fn(AppDataType) -> Result<PathBuf, AppDirsError>,
+{
+    let mut groups: Vec<(PathBuf, Vec<AppDataType>)> = Vec::new();
+    for t in AppDataType::all() {
+        let path = match root(t) {
+            Ok(path) => path,
+            Err(AppDirsError::NotSupported) => continue,
+            Err(e) => return Err(e),
+        };
+        match groups.iter_mut().find(|(p, _)| same_path(p, &path)) {
+            Some((_, types)) => types.push(t),
+            None => groups.push((path, vec![t])),
+        }
+    }
+    Ok(groups)
+}
+
+fn tally(dir: &Path, size: &mut u64, file_count: &mut u64) -> Result<(), AppDirsError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // Removed while we were looking.
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            tally(&entry.path(), size, file_count)?;
+        } else if file_type.is_file() {
+            *size += entry.metadata()?.len();
+            *file_count += 1;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,329 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo, ContextError, ErrorContext};
+use crate::utils;
+use crate::AppDataType::*;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+
+/// A platform whose directory layout can be computed on any host with
+/// [`target_data_root`](fn.target_data_root.html) and friends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TargetPlatform {
+    /// Windows, using the known folders.
+    Windows,
+    /// macOS and iOS, using `~/Library`.
+    MacOs,
+    /// Linux, the BSDs and other *nix systems, using the XDG base directories.
+    Unix,
+    /// Android, using the directories of the app's `Context`.
+    Android,
+}
+
+impl TargetPlatform {
+    /// Returns the platform this program was compiled for, or `None` if the
+    /// library does not support it.
+    #[must_use]
+    pub fn current() -> Option<TargetPlatform> {
+        if cfg!(windows) {
+            Some(TargetPlatform::Windows)
+        } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+            Some(TargetPlatform::MacOs)
+        } else if cfg!(target_os = "android") {
+            Some(TargetPlatform::Android)
+        } else if cfg!(unix) {
+            Some(TargetPlatform::Unix)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the path separator of the platform.
+    #[must_use]
+    pub fn separator(self) -> char {
+        match self {
+            TargetPlatform::Windows => '\\',
+            TargetPlatform::MacOs | TargetPlatform::Unix | TargetPlatform::Android => '/',
+        }
+    }
+
+    /// Returns `true` if app directories on the platform include the app's
+    /// author in addition to its name.
+    #[must_use]
+    pub const fn uses_author(self) -> bool {
+        matches!(self, TargetPlatform::Windows)
+    }
+}
+
+/// The platform-specific inputs needed to compute a
+/// [`TargetPlatform`](enum.TargetPlatform.html)'s layout.
+///
+/// Only the fields relevant for the target platform are used; a missing
+/// input makes the data types that depend on it fail with
+/// `AppDirsError::NotSupported`, with the missing input named in the
+/// [`ContextError`](struct.ContextError.html)'s context.
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let mut dirs = TargetDirs::default();
+/// dirs.roaming_app_data = Some(r"C:\Users\Rusty\AppData\Roaming".into());
+/// let config = target_app_root(TargetPlatform::Windows, AppDataType::UserConfig, &APP_INFO, &dirs);
+/// assert_eq!(r"C:\Users\Rusty\AppData\Roaming\SuperDev\CoolApp", config.unwrap());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct TargetDirs {
+    /// The user's home directory (macOS and *nix).
+    pub home: Option<String>,
+    /// `FOLDERID_RoamingAppData` (Windows).
+    pub roaming_app_data: Option<String>,
+    /// `FOLDERID_LocalAppData` (Windows).
+    pub local_app_data: Option<String>,
+    /// `FOLDERID_ProgramData` (Windows).
+    pub program_data: Option<String>,
+    /// `$XDG_CONFIG_HOME` (*nix).
+    pub xdg_config_home: Option<String>,
+    /// `$XDG_DATA_HOME` (*nix).
+    pub xdg_data_home: Option<String>,
+    /// `$XDG_CACHE_HOME` (*nix).
+    pub xdg_cache_home: Option<String>,
+    /// `$XDG_DATA_DIRS` (*nix), a colon-separated list.
+    pub xdg_data_dirs: Option<String>,
+    /// `$XDG_CONFIG_DIRS` (*nix), a colon-separated list.
+    pub xdg_config_dirs: Option<String>,
+    /// `Context.getDataDir()` (Android).
+    pub android_data_dir: Option<String>,
+    /// `Context.getFilesDir()` (Android).
+    pub android_files_dir: Option<String>,
+    /// `Context.getCacheDir()` (Android).
+    pub android_cache_dir: Option<String>,
+    /// `Context.getExternalFilesDir(null)` (Android).
+    pub android_external_files_dir: Option<String>,
+}
+
+/// Returns the **top-level** data directory for the provided data type on
+/// the given platform, computed from `dirs` rather than the current system.
+///
+/// This mirrors what [`get_data_root`](fn.get_data_root.html) returns on that
+/// platform, except that the optional backends of the *nix implementation
+/// (see [`UnixOptions`](struct.UnixOptions.html)) are not emulated.
+pub fn target_data_root(
+    target: TargetPlatform,
+    t: AppDataType,
+    dirs: &TargetDirs,
+) -> Result<String, ContextError> {
+    match target {
+        TargetPlatform::Windows => {
+            let folder = KnownFolder::for_data_type(t);
+            let path = match folder {
+                KnownFolder::RoamingAppData => &dirs.roaming_app_data,
+                KnownFolder::LocalAppData => &dirs.local_app_data,
+                KnownFolder::ProgramData => &dirs.program_data,
+            };
+            path.clone().ok_or_else(|| {
+                missing_input(t, "windows", |c| c.api_call = Some(folder.api_call()))
+            })
+        },
+        TargetPlatform::MacOs => {
+            let default = macos_default(t);
+            let home = home_for(&default, dirs)
+                .ok_or_else(|| missing_input(t, "macos", |c| c.env_var = Some("HOME")))?;
+            Ok(default.join_str(home, '/'))
+        },
+        TargetPlatform::Unix => {
+            let var = match t {
+                UserConfig => &dirs.xdg_config_home,
+                UserData => &dirs.xdg_data_home,
+                UserCache => &dirs.xdg_cache_home,
+                SharedData => &dirs.xdg_data_dirs,
+                SharedConfig => &dirs.xdg_config_dirs,
+            };
+            // Like the XDG backend, ignore relative paths.
+            let from_var = var.as_ref().and_then(|value| {
+                if t.is_shared() {
+                    value.split(':').find(|dir| dir.starts_with('/'))
+                } else {
+                    Some(value.as_str()).filter(|dir| dir.starts_with('/'))
+                }
+            });
+            if let Some(dir) = from_var {
+                return Ok(dir.to_owned());
+            }
+            let default = xdg_default(t);
+            let home = home_for(&default, dirs)
+                .ok_or_else(|| missing_input(t, "xdg", |c| c.env_var = Some("HOME")))?;
+            Ok(default.join_str(home, '/'))
+        },
+        TargetPlatform::Android => {
+            let (method, _) = android_method(t);
+            let path = match t {
+                UserConfig => &dirs.android_data_dir,
+                UserData => &dirs.android_files_dir,
+                UserCache => &dirs.android_cache_dir,
+                SharedData | SharedConfig => &dirs.android_external_files_dir,
+            };
+            path.clone()
+                .ok_or_else(|| missing_input(t, "android", |c| c.api_call = Some(method)))
+        },
+    }
+}
+
+/// Returns the **app-specific** data directory for the provided data type on
+/// the given platform. (See
+/// [`target_data_root`](fn.target_data_root.html).)
+pub fn target_app_root(
+    target: TargetPlatform,
+    t: AppDataType,
+    app: &AppInfo,
+    dirs: &TargetDirs,
+) -> Result<String, ContextError> {
+    target_app_dir(target, t, app, "", dirs)
+}
+
+/// Returns the **app-specific** data **subdirectory** for the provided data
+/// type and subdirectory path on the given platform. (See
+/// [`target_data_root`](fn.target_data_root.html).)
+///
+/// The `path` parameter should be a valid relative path separated by
+/// **forward slashes** (`/`).
+pub fn target_app_dir(
+    target: TargetPlatform,
+    t: AppDataType,
+    app: &AppInfo,
+    path: &str,
+    dirs: &TargetDirs,
+) -> Result<String, ContextError> {
+    if app.author.is_empty() || app.name.is_empty() {
+        return Err(AppDirsError::InvalidAppInfo.into());
+    }
+    let mut root = target_data_root(target, t, dirs)?;
+    let sep = target.separator();
+    let mut push = |component: &str| {
+        if !root.ends_with(sep) {
+            root.push(sep);
+        }
+        root.push_str(&utils::sanitized(component));
+    };
+    if target.uses_author() {
+        push(app.author);
+    }
+    push(app.name);
+    for component in path.split('/').filter(|s| !s.is_empty()) {
+        push(component);
+    }
+    Ok(root)
+}
+
+fn missing_input<F>(t: AppDataType, backend: &'static str, f: F) -> ContextError
+where
+    F: FnOnce(&mut ErrorContext),
+{
+    AppDirsError::NotSupported.with_context(|c| {
+        c.data_type = Some(t);
+        c.backend = Some(backend);
+        f(c);
+    })
+}
+
+fn home_for<'a>(default: &DefaultDir, dirs: &'a TargetDirs) -> Option<&'a str> {
+    if default.in_home {
+        dirs.home.as_deref().filter(|home| !home.is_empty())
+    } else {
+        Some("")
+    }
+}
+
+/// The Windows known folder a data type maps onto.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)] // named after the `FOLDERID_*` constants
+pub(crate) enum KnownFolder {
+    RoamingAppData,
+    LocalAppData,
+    ProgramData,
+}
+
+impl KnownFolder {
+    pub(crate) fn for_data_type(t: AppDataType) -> KnownFolder {
+        match t {
+            UserConfig => KnownFolder::RoamingAppData,
+            SharedConfig | SharedData => KnownFolder::ProgramData,
+            UserCache | UserData => KnownFolder::LocalAppData,
+        }
+    }
+
+    pub(crate) fn api_call(self) -> &'static str {
+        match self {
+            KnownFolder::RoamingAppData => "SHGetKnownFolderPath(FOLDERID_RoamingAppData)",
+            KnownFolder::LocalAppData => "SHGetKnownFolderPath(FOLDERID_LocalAppData)",
+            KnownFolder::ProgramData => "SHGetKnownFolderPath(FOLDERID_ProgramData)",
+        }
+    }
+}
+
+/// A platform's default directory for a data type: path segments below
+/// either the user's home directory or the file system root.
+pub(crate) struct DefaultDir {
+    pub in_home: bool,
+    pub segments: &'static [&'static str],
+}
+
+impl DefaultDir {
+    #[cfg(unix)]
+    pub(crate) fn to_path(&self, home: &Path) -> PathBuf {
+        let mut path = if self.in_home {
+            home.to_owned()
+        } else {
+            PathBuf::from("/")
+        };
+        path.extend(self.segments);
+        path
+    }
+
+    fn join_str(&self, home: &str, sep: char) -> String {
+        let mut path = home.trim_end_matches(sep).to_owned();
+        for segment in self.segments {
+            path.push(sep);
+            path.push_str(segment);
+        }
+        path
+    }
+}
+
+pub(crate) fn macos_default(t: AppDataType) -> DefaultDir {
+    DefaultDir {
+        in_home: !t.is_shared(),
+        segments: match t {
+            UserConfig | UserData | SharedConfig | SharedData => {
+                &["Library", "Application Support"]
+            },
+            UserCache => &["Library", "Caches"],
+        },
+    }
+}
+
+/// Returns the XDG default for the given data type, i.e. the directory used
+/// when none of the `XDG_*` variables are set.
+pub(crate) fn xdg_default(t: AppDataType) -> DefaultDir {
+    DefaultDir {
+        in_home: !t.is_shared(),
+        segments: match t {
+            UserConfig => &[".config"],
+            UserData => &[".local", "share"],
+            UserCache => &[".cache"],
+            SharedData => &["usr", "local", "share"],
+            SharedConfig => &["etc", "xdg"],
+        },
+    }
+}
+
+/// Returns the `Context` method Android uses for the given data type, and
+/// whether it takes a (null) `String` argument.
+pub(crate) fn android_method(t: AppDataType) -> (&'static str, bool) {
+    match t {
+        UserConfig => ("getDataDir", false),
+        UserData => ("getFilesDir", false),
+        UserCache => ("getCacheDir", false),
+        SharedData | SharedConfig => ("getExternalFilesDir", true),
+        // SharedCache => ("getExternalCacheDir", false),
+    }
+}
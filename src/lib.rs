@@ -38,12 +38,50 @@
 //! }
 //! ```
 
+#[cfg(feature = "tokio")]
+mod async_fs;
+#[cfg(feature = "tokio")]
+pub use crate::async_fs::*;
+#[cfg(feature = "backup")]
+mod backup;
+#[cfg(feature = "backup")]
+pub use crate::backup::*;
 mod common;
 pub use crate::common::*;
+#[cfg(any(feature = "toml", feature = "json"))]
+mod config;
+#[cfg(any(feature = "toml", feature = "json"))]
+pub use crate::config::*;
+mod doctor;
+pub use crate::doctor::*;
+mod drop_in;
+pub use crate::drop_in::*;
+mod export;
+pub use crate::export::*;
 mod imp;
 pub use crate::imp::*;
+mod inventory;
+pub use crate::inventory::*;
+mod layout;
+pub use crate::layout::*;
+mod profiles;
+pub use crate::profiles::*;
+mod roots;
+pub use crate::roots::*;
+mod temp;
+pub use crate::temp::*;
+#[cfg(feature = "test-support")]
+mod test_support;
+#[cfg(feature = "test-support")]
+pub use crate::test_support::*;
 mod utils;
 pub use crate::utils::*;
+mod versioned;
+pub use crate::versioned::*;
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "watch")]
+pub use crate::watch::*;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,196 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo, ContextError};
+use crate::imp::{create_dir_all, get_app_root, push_relative};
+use crate::utils::{self, same_path};
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+
+/// The directory below the app directory that holds the profiles.
+pub(crate) const PROFILES_DIR: &str = "profiles";
+
+/// The data types that [`create_profile`](fn.create_profile.html),
+/// [`list_profiles`](fn.list_profiles.html),
+/// [`rename_profile`](fn.rename_profile.html) and
+/// [`delete_profile`](fn.delete_profile.html) act on.
+///
+/// Shared profile directories can still be resolved with
+/// [`get_profile_root`](fn.get_profile_root.html), but are usually not
+/// writable by the app, so they are left out.
+pub const PROFILE_DATA_TYPES: [AppDataType; 3] = [
+    AppDataType::UserConfig,
+    AppDataType::UserData,
+    AppDataType::UserCache,
+];
+
+/// Returns (but **does not create**) path to the data directory of a named
+/// profile of the app, i.e. `profiles/<profile>` below the **app-specific**
+/// data directory for provided data type.
+///
+/// The profile name is sanitized like the app name; if it is empty,
+/// `AppDirsError::InvalidAppInfo` is returned.
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let work = get_profile_root(AppDataType::UserConfig, &APP_INFO, "work");
+/// // *nix: "$HOME/.config/CoolApp/profiles/work"
+/// ```
+pub fn get_profile_root(
+    t: AppDataType,
+    app: &AppInfo,
+    profile: &str,
+) -> Result<PathBuf, AppDirsError> {
+    if profile.is_empty() {
+        return Err(AppDirsError::InvalidAppInfo);
+    }
+    get_profiles_dir(t, app).map(|mut root| {
+        root.push(utils::sanitized(profile));
+        root
+    })
+}
+
+/// Creates (if necessary) and returns path to the data directory of a named
+/// profile. (See [`get_profile_root`](fn.get_profile_root.html).)
+pub fn profile_root(t: AppDataType, app: &AppInfo, profile: &str) -> Result<PathBuf, AppDirsError> {
+    let path = get_profile_root(t, app, profile)?;
+    create_dir_all(t, path).map_err(ContextError::into_error)
+}
+
+/// Returns (but **does not create**) path to a **subdirectory** of a named
+/// profile's data directory. (See
+/// [`get_profile_root`](fn.get_profile_root.html).)
+///
+/// The `path` parameter should be a valid relative path separated by
+/// **forward slashes** (`/`).
+pub fn get_profile_dir(
+    t: AppDataType,
+    app: &AppInfo,
+    profile: &str,
+    path: &str,
+) -> Result<PathBuf, AppDirsError> {
+    get_profile_root(t, app, profile).map(|root| push_relative(root, path))
+}
+
+/// Creates (if necessary) and returns path to a **subdirectory** of a named
+/// profile's data directory. (See
+/// [`get_profile_dir`](fn.get_profile_dir.html).)
+pub fn profile_dir(
+    t: AppDataType,
+    app: &AppInfo,
+    profile: &str,
+    path: &str,
+) -> Result<PathBuf, AppDirsError> {
+    let path = get_profile_dir(t, app, profile, path)?;
+    create_dir_all(t, path).map_err(ContextError::into_error)
+}
+
+/// Creates the data directories of a profile for every data type in
+/// [`PROFILE_DATA_TYPES`](constant.PROFILE_DATA_TYPES.html).
+pub fn create_profile(app: &AppInfo, profile: &str) -> Result<(), AppDirsError> {
+    for (t, _) in profile_roots(app, profile)? {
+        profile_root(t, app, profile)?;
+    }
+    Ok(())
+}
+
+/// Lists the profiles that have a data directory for any data type in
+/// [`PROFILE_DATA_TYPES`](constant.PROFILE_DATA_TYPES.html), sorted and
+/// without duplicates. The names are returned as they appear on disk (i.e.
+/// sanitized).
+pub fn list_profiles(app: &AppInfo) -> Result<Vec<String>, AppDirsError> {
+    let mut profiles = Vec::new();
+    for (_, dir) in unique_roots(app, |t| get_profiles_dir(t, app))? {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            if entry.path().is_dir() {
+                if let Ok(name) = entry.file_name().into_string() {
+                    profiles.push(name);
+                }
+            }
+        }
+    }
+    profiles.sort();
+    profiles.dedup();
+    Ok(profiles)
+}
+
+/// Renames a profile for every data type in
+/// [`PROFILE_DATA_TYPES`](constant.PROFILE_DATA_TYPES.html).
+///
+/// Fails without renaming anything if no directory of profile `from` exists
+/// (`ErrorKind::NotFound`) or a directory of profile `to` already exists
+/// (`ErrorKind::AlreadyExists`).
+pub fn rename_profile(app: &AppInfo, from: &str, to: &str) -> Result<(), AppDirsError> {
+    let renames: Vec<_> = profile_roots(app, from)?
+        .into_iter()
+        .filter(|(_, path)| path.exists())
+        .map(|(t, path)| get_profile_root(t, app, to).map(|target| (t, path, target)))
+        .collect::<Result<_, _>>()?;
+    if renames.is_empty() {
+        let e = Error::new(ErrorKind::NotFound, format!("no profile named {:?}", from));
+        return Err(e.into());
+    }
+    if renames.iter().any(|(_, _, target)| target.exists()) {
+        let e = Error::new(ErrorKind::AlreadyExists, format!("profile {:?} exists", to));
+        return Err(e.into());
+    }
+    for (_, path, target) in renames {
+        fs::rename(&path, &target)?;
+    }
+    Ok(())
+}
+
+/// Deletes the data directories of a profile, including their contents, for
+/// every data type in
+/// [`PROFILE_DATA_TYPES`](constant.PROFILE_DATA_TYPES.html). Deleting a
+/// profile that does not exist succeeds.
+pub fn delete_profile(app: &AppInfo, profile: &str) -> Result<(), AppDirsError> {
+    for (_, path) in profile_roots(app, profile)? {
+        match fs::remove_dir_all(&path) {
+            Ok(()) => {},
+            Err(ref e) if e.kind() == ErrorKind::NotFound => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+fn get_profiles_dir(t: AppDataType, app: &AppInfo) -> Result<PathBuf, AppDirsError> {
+    get_app_root(t, app).map(|root| root.join(PROFILES_DIR))
+}
+
+/// Returns the profile's data directory for every supported data type in
+/// `PROFILE_DATA_TYPES`, skipping data types that share a directory with an
+/// earlier one (e.g. `UserData` and `UserCache` on Windows).
+fn profile_roots(
+    app: &AppInfo,
+    profile: &str,
+) -> Result<Vec<(AppDataType, PathBuf)>, AppDirsError> {
+    unique_roots(app, |t| get_profile_root(t, app, profile))
+}
+
+fn unique_roots<F>(app: &AppInfo, root: F) -> Result<Vec<(AppDataType, PathBuf)>, AppDirsError>
+where
+    F: Fn(AppDataType) -> Result<PathBuf, AppDirsError>,
+{
+    if app.author.is_empty() || app.name.is_empty() {
+        return Err(AppDirsError::InvalidAppInfo);
+    }
+    let mut roots: Vec<(AppDataType, PathBuf)> = Vec::new();
+    for &t in &PROFILE_DATA_TYPES {
+        let path = match root(t) {
+            Ok(path) => path,
+            Err(AppDirsError::NotSupported) => continue,
+            Err(e) => return Err(e),
+        };
+        if !roots.iter().any(|(_, p)| same_path(p, &path)) {
+            roots.push((t, path));
+        }
+    }
+    Ok(roots)
+}
@@ -0,0 +1,63 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo, OwnedAppInfo};
+use crate::imp::get_app_root;
+use std::path::{Path, PathBuf};
+
+/// Snapshot of the **app-specific** data directory of every data type.
+///
+/// Useful for handing the resolved layout to another process or persisting
+/// it; with the `serde` feature enabled, `AppRoots` can be serialized and
+/// deserialized (fields use kebab-case names, e.g. `"user-config"`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub struct AppRoots {
+    /// The app the directories belong to.
+    pub app: OwnedAppInfo,
+    /// See [`AppDataType::UserConfig`](enum.AppDataType.html#variant.UserConfig).
+    pub user_config: Option<PathBuf>,
+    /// See [`AppDataType::UserData`](enum.AppDataType.html#variant.UserData).
+    pub user_data: Option<PathBuf>,
+    /// See [`AppDataType::UserCache`](enum.AppDataType.html#variant.UserCache).
+    pub user_cache: Option<PathBuf>,
+    /// See [`AppDataType::SharedData`](enum.AppDataType.html#variant.SharedData).
+    pub shared_data: Option<PathBuf>,
+    /// See [`AppDataType::SharedConfig`](enum.AppDataType.html#variant.SharedConfig).
+    pub shared_config: Option<PathBuf>,
+}
+
+impl AppRoots {
+    /// Returns (but **does not create**) the app-specific data directory of
+    /// every data type, as [`get_app_root`](fn.get_app_root.html) would.
+    ///
+    /// Data types that are not supported on this system are `None`.
+    pub fn resolve(app: &AppInfo) -> Result<AppRoots, AppDirsError> {
+        if app.author.is_empty() || app.name.is_empty() {
+            return Err(AppDirsError::InvalidAppInfo);
+        }
+        let root = |t| get_app_root(t, app).ok();
+        Ok(AppRoots {
+            app: OwnedAppInfo::from(app),
+            user_config: root(AppDataType::UserConfig),
+            user_data: root(AppDataType::UserData),
+            user_cache: root(AppDataType::UserCache),
+            shared_data: root(AppDataType::SharedData),
+            shared_config: root(AppDataType::SharedConfig),
+        })
+    }
+
+    /// Returns the directory recorded for the given data type, if any.
+    #[must_use]
+    pub fn get(&self, t: AppDataType) -> Option<&Path> {
+        let path = match t {
+            AppDataType::UserConfig => &self.user_config,
+            AppDataType::UserData => &self.user_data,
+            AppDataType::UserCache => &self.user_cache,
+            AppDataType::SharedData => &self.shared_data,
+            AppDataType::SharedConfig => &self.shared_config,
+        };
+        path.as_deref()
+    }
+}
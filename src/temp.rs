@@ -0,0 +1,320 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::{app_dir, get_app_dir};
+use std::collections::hash_map::RandomState;
+use std::fs::{self, File, OpenOptions};
+use std::hash::BuildHasher;
+use std::io::{self, ErrorKind};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// The directory below the app's cache directory that holds the temporary
+/// directories.
+pub(crate) const TEMP_DIR: &str = "tmp";
+
+/// Entries modified more recently than this are never swept, which also
+/// covers the window between creating an entry and locking it.
+const MIN_SWEEP_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// A temporary directory below the app's cache directory, deleted with its
+/// contents when dropped. (See [`app_temp_dir`](fn.app_temp_dir.html).)
+#[derive(Debug)]
+pub struct AppTempDir {
+    path: Option<PathBuf>,
+    lock: Option<File>,
+}
+
+impl AppTempDir {
+    /// Returns the path of the directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.path.as_deref().expect("path taken")
+    }
+
+    /// Keeps the directory instead of deleting it, returning its path.
+    #[must_use]
+    pub fn keep(mut self) -> PathBuf {
+        if let Some(lock) = self.lock.take() {
+            hold_until_exit(lock);
+        }
+        self.path.take().expect("path taken")
+    }
+}
+
+impl Drop for AppTempDir {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.path {
+            let _ = fs::remove_dir_all(path);
+        }
+    }
+}
+
+/// A temporary file below the app's cache directory, deleted when dropped.
+/// (See [`app_temp_file`](fn.app_temp_file.html).)
+#[derive(Debug)]
+pub struct AppTempFile {
+    path: Option<PathBuf>,
+    file: File,
+}
+
+impl AppTempFile {
+    /// Returns the path of the file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.path.as_deref().expect("path taken")
+    }
+
+    /// Returns the open file.
+    #[must_use]
+    pub fn as_file(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns the open file.
+    #[must_use]
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Keeps the file instead of deleting it, returning its path.
+    #[must_use]
+    pub fn keep(mut self) -> PathBuf {
+        // A duplicate shares the lock of the file.
+        if let Ok(lock) = self.file.try_clone() {
+            hold_until_exit(lock);
+        }
+        self.path.take().expect("path taken")
+    }
+}
+
+impl Drop for AppTempFile {
+    fn drop(&mut self) {
+        if let Some(ref path) = self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Creates a new, empty temporary directory in the `tmp` subdirectory of the
+/// **app-specific** `UserCache` directory, which is deleted with its contents
+/// when the returned guard is dropped.
+///
+/// The guard marks the directory as in use (see
+/// [`sweep_app_temp`](fn.sweep_app_temp.html)), so that other processes of
+/// the app can clean up after processes that exited without dropping their
+/// guards. A kept directory stays in use until the process exits.
+///
+/// ```no_run
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let scratch = app_temp_dir(&APP_INFO).unwrap();
+/// std::fs::write(scratch.path().join("download.part"), b"...").unwrap();
+/// // Deleted here.
+/// drop(scratch);
+/// ```
+pub fn app_temp_dir(app: &AppInfo) -> Result<AppTempDir, AppDirsError> {
+    let path = create_unique(app, "", |path| fs::create_dir(path))?;
+    let mut dir = AppTempDir {
+        path: Some(path),
+        lock: None,
+    };
+    dir.lock = lock_dir(dir.path())?;
+    Ok(dir)
+}
+
+/// Creates and opens (for reading and writing) a new, empty temporary file
+/// in the `tmp` subdirectory of the **app-specific** `UserCache` directory,
+/// which is deleted when the returned guard is dropped. The file name ends
+/// with `suffix` (e.g. `".json"`). (See
+/// [`app_temp_dir`](fn.app_temp_dir.html).)
+pub fn app_temp_file(app: &AppInfo, suffix: &str) -> Result<AppTempFile, AppDirsError> {
+    let mut file = None;
+    let path = create_unique(app, suffix, |path| {
+        file = Some(
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(path)?,
+        );
+        Ok(())
+    })?;
+    let file = AppTempFile {
+        path: Some(path),
+        file: file.expect("file created"),
+    };
+    lock_shared(&file.file)?;
+    Ok(file)
+}
+
+/// Deletes the temporary directories and files left behind by processes of
+/// the app that are no longer running (e.g. because they crashed), returning
+/// how many were deleted.
+///
+/// Entries modified within the last hour are never deleted. On Unix, the
+/// guards hold a lock on their entries, and entries are deleted only if
+/// nothing holds a lock on them, which also works across PID namespaces
+/// (e.g. between Flatpak sandboxes) sharing the cache directory. Elsewhere,
+/// entries are deleted only if no process with the id in their name is
+/// running, which may keep them around until the id is reused by an
+/// unrelated process.
+pub fn sweep_app_temp(app: &AppInfo) -> Result<usize, AppDirsError> {
+    let dir = get_app_dir(AppDataType::UserCache, app, TEMP_DIR)?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let entry = entry?;
+        let pid = match entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.split('-').next())
+            .and_then(|pid| pid.parse::<u32>().ok())
+        {
+            Some(pid) => pid,
+            None => continue,
+        };
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        // Symlinks and special files are not ours.
+        if !metadata.is_dir() && !metadata.is_file() {
+            continue;
+        }
+        let is_recent = metadata
+            .modified()?
+            .elapsed()
+            .map_or(true, |age| age < MIN_SWEEP_AGE);
+        if is_recent {
+            continue;
+        }
+        let path = entry.path();
+        match is_in_use(&path, pid) {
+            Ok(false) => {},
+            Ok(true) => continue,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        }
+        let result = if metadata.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        match result {
+            Ok(()) => removed += 1,
+            // Another sweeper got there first.
+            Err(ref e) if e.kind() == ErrorKind::NotFound => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(removed)
+}
+
+/// Creates an entry with a new unique name in the app's temporary directory.
+fn create_unique<F>(app: &AppInfo, suffix: &str, mut create: F) -> Result<PathBuf, AppDirsError>
+where
+    F: FnMut(&Path) -> std::io::Result<()>,
+{
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = app_dir(AppDataType::UserCache, app, TEMP_DIR)?;
+    let state = RandomState::new();
+    loop {
+        let unique = state.hash_one(COUNTER.fetch_add(1, Ordering::Relaxed));
+        let name = format!("{}-{:016x}{}", process::id(), unique, suffix);
+        let path = dir.join(name);
+        match create(&path) {
+            Ok(()) => return Ok(path),
+            Err(ref e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Leaks the lock of a kept entry, so that it stays in use until the process
+/// exits.
+fn hold_until_exit(lock: File) {
+    mem::forget(lock);
+}
+
+/// Opens and locks a directory created by [`app_temp_dir`].
+#[cfg(unix)]
+fn lock_dir(path: &Path) -> io::Result<Option<File>> {
+    let dir = File::open(path)?;
+    lock_shared(&dir)?;
+    Ok(Some(dir))
+}
+
+#[cfg(not(unix))]
+fn lock_dir(_path: &Path) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+/// Marks an entry as in use for as long as `file` is open.
+#[cfg(unix)]
+fn lock_shared(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(unix))]
+fn lock_shared(_file: &File) -> io::Result<()> {
+    Ok(())
+}
+
+/// Checks whether an entry is locked by a guard.
+#[cfg(unix)]
+fn is_in_use(path: &Path, _pid: u32) -> io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+    let entry = File::open(path)?;
+    // The lock is released again when the file is closed.
+    if unsafe { libc::flock(entry.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(false);
+    }
+    let e = io::Error::last_os_error();
+    if e.kind() == ErrorKind::WouldBlock {
+        Ok(true)
+    } else {
+        Err(e)
+    }
+}
+
+/// Checks whether the process that created an entry is running.
+#[cfg(not(unix))]
+fn is_in_use(_path: &Path, pid: u32) -> io::Result<bool> {
+    Ok(pid == process::id() || is_running(pid))
+}
+
+#[cfg(windows)]
+fn is_running(pid: u32) -> bool {
+    use windows::Win32::Foundation::{CloseHandle, ERROR_ACCESS_DENIED, STILL_ACTIVE};
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let process = match unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) } {
+        Ok(process) => process,
+        Err(e) => return e.code() == ERROR_ACCESS_DENIED.to_hresult(),
+    };
+    let mut code = 0;
+    let running = unsafe { GetExitCodeProcess(process, &mut code) }
+        .is_ok_and(|()| code == STILL_ACTIVE.0 as u32);
+    let _ = unsafe { CloseHandle(process) };
+    running
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_running(_pid: u32) -> bool {
+    // Without a way to tell, assume the worst.
+    true
+}
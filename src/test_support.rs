@@ -0,0 +1,165 @@
+use crate::common::{AppDataType, ResolutionSource};
+use crate::imp::Resolved;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+thread_local! {
+    static OVERRIDE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// A temporary directory standing in for all top-level data directories of
+/// the current thread, for use in tests.
+///
+/// While a `FakeHome` is alive, [`get_data_root`](fn.get_data_root.html) and
+/// everything built on it resolve to a subdirectory of the fake home named
+/// after the data type (e.g. `user-config`), without touching environment
+/// variables. The override only applies to the thread that created the
+/// `FakeHome` (including the `*_async` functions called on it), so tests can
+/// run in parallel. Dropping it restores the
+/// previous resolution and deletes the directory tree.
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let home = FakeHome::new().unwrap();
+/// app_dir(AppDataType::UserCache, &APP_INFO, "images").unwrap();
+/// if cfg!(windows) {
+///     home.assert_created_only(&["user-cache/SuperDev/CoolApp/images"]);
+/// } else {
+///     home.assert_created_only(&["user-cache/CoolApp/images"]);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FakeHome {
+    dir: tempfile::TempDir,
+    previous: Option<PathBuf>,
+    // The override is thread-local, so the guard must stay on its thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl FakeHome {
+    /// Creates an empty fake home in the system's temporary directory and
+    /// points resolution on the current thread at it.
+    pub fn new() -> io::Result<FakeHome> {
+        let dir = tempfile::Builder::new().prefix("app_dirs2-").tempdir()?;
+        let previous = OVERRIDE.with(|o| o.replace(Some(dir.path().to_owned())));
+        Ok(FakeHome {
+            dir,
+            previous,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Returns the path of the fake home.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Returns the top-level data directory the given data type resolves to
+    /// while this fake home is active.
+    #[must_use]
+    pub fn data_root(&self, t: AppDataType) -> PathBuf {
+        self.path().join(t.name())
+    }
+
+    /// Returns every directory below the fake home, relative to it and
+    /// sorted.
+    pub fn created_dirs(&self) -> io::Result<Vec<PathBuf>> {
+        let mut dirs = Vec::new();
+        collect_dirs(self.path(), self.path(), &mut dirs)?;
+        dirs.sort();
+        Ok(dirs)
+    }
+
+    /// Panics unless the directories below the fake home are exactly the
+    /// given ones (relative to the fake home, separated by forward slashes)
+    /// and their ancestors.
+    #[track_caller]
+    pub fn assert_created_only<P: AsRef<str>>(&self, expected: &[P]) {
+        let expected: BTreeSet<PathBuf> = expected
+            .iter()
+            .flat_map(|path| {
+                let path: PathBuf = path.as_ref().split('/').filter(|s| !s.is_empty()).collect();
+                path.ancestors()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .map(Path::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let actual: BTreeSet<PathBuf> = self
+            .created_dirs()
+            .expect("failed to list the fake home")
+            .into_iter()
+            .collect();
+        if actual != expected {
+            panic!(
+                "unexpected directories in {}:\n  missing: {:?}\n  unexpected: {:?}",
+                self.path().display(),
+                expected.difference(&actual).collect::<Vec<_>>(),
+                actual.difference(&expected).collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    /// Panics if anything was created in the fake home.
+    #[track_caller]
+    pub fn assert_empty(&self) {
+        self.assert_created_only::<&str>(&[]);
+    }
+}
+
+impl Drop for FakeHome {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        OVERRIDE.with(|o| *o.borrow_mut() = previous);
+    }
+}
+
+fn collect_dirs(base: &Path, dir: &Path, dirs: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let path = entry.path();
+            dirs.push(path.strip_prefix(base).unwrap_or(&path).to_owned());
+            collect_dirs(base, &path, dirs)?;
+        }
+    }
+    Ok(())
+}
+
+/// Wraps `f` so that it sees the `FakeHome` of the current thread (if any)
+/// when run on another thread.
+#[cfg(feature = "tokio")]
+pub(crate) fn propagate_override<F, R>(f: F) -> impl FnOnce() -> R + Send + 'static
+where
+    F: FnOnce() -> R + Send + 'static,
+{
+    struct Restore(Option<PathBuf>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            OVERRIDE.with(|o| *o.borrow_mut() = self.0.take());
+        }
+    }
+    let home = OVERRIDE.with(|o| o.borrow().clone());
+    move || {
+        let _restore = Restore(OVERRIDE.with(|o| o.replace(home)));
+        f()
+    }
+}
+
+/// Returns the overridden data directory, if a `FakeHome` is active on the
+/// current thread.
+pub(crate) fn resolve_override(t: AppDataType) -> Option<Resolved> {
+    OVERRIDE.with(|o| {
+        o.borrow().as_ref().map(|home| Resolved {
+            path: home.join(t.name()),
+            backend: "test-support",
+            source: ResolutionSource::Override,
+        })
+    })
+}
@@ -1,3 +1,6 @@
+use std::fs;
+use std::path::Path;
+
 /// Returns a cross-platform-filename-safe version of any string.
 ///
 /// This is used internally to generate app data directories based on app
@@ -25,3 +28,18 @@ pub fn sanitized(component: &str) -> String {
     }
     buf
 }
+
+/// Returns `true` if both paths refer to the same location, either literally
+/// or after resolving symlinks (which requires both to exist).
+pub(crate) fn same_path(a: &Path, b: &Path) -> bool {
+    a == b || fs::canonicalize(a).is_ok_and(|a| fs::canonicalize(b).is_ok_and(|b| a == b))
+}
+
+/// Returns `true` if `inner` lies strictly below `outer`, either literally or
+/// after resolving symlinks (which requires both to exist).
+pub(crate) fn is_below(inner: &Path, outer: &Path) -> bool {
+    let below = |inner: &Path, outer: &Path| inner != outer && inner.starts_with(outer);
+    below(inner, outer)
+        || fs::canonicalize(inner)
+            .is_ok_and(|inner| fs::canonicalize(outer).is_ok_and(|outer| below(&inner, &outer)))
+}
@@ -0,0 +1,196 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo, ContextError};
+use crate::imp::{create_dir_all, get_app_root, push_relative};
+use crate::profiles::PROFILES_DIR;
+use crate::temp::TEMP_DIR;
+use crate::utils;
+use std::cmp::Ordering;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Directories that other parts of this library create below the app
+/// directory, which are not versions.
+const RESERVED_NAMES: [&str; 2] = [PROFILES_DIR, TEMP_DIR];
+
+/// An app together with a version or release channel that scopes some of its
+/// data directories.
+///
+/// For the data types listed in `scoped`, the versioned functions insert a
+/// sanitized `version` segment below the app directory (e.g.
+/// `~/.config/CoolApp/3.x`); the other data types are shared between versions.
+/// Like `AppInfo`, it can be created in `const` position:
+///
+/// ```
+/// use app_dirs2::*;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// const BETA: VersionedAppInfo = VersionedAppInfo::new(APP_INFO, "3.x");
+/// let config = get_versioned_app_root(AppDataType::UserConfig, &BETA);
+/// // *nix: "$HOME/.config/CoolApp/3.x"
+/// let cache = get_versioned_app_root(AppDataType::UserCache, &BETA);
+/// // *nix: "$HOME/.cache/CoolApp"
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VersionedAppInfo {
+    /// The app.
+    pub app: AppInfo,
+    /// The version or channel (e.g. `"3.x"` or `"beta"`).
+    pub version: &'static str,
+    /// The data types whose directories are scoped by version.
+    pub scoped: &'static [AppDataType],
+}
+
+impl VersionedAppInfo {
+    /// Scopes every data type except `UserCache` by `version`.
+    #[must_use]
+    pub const fn new(app: AppInfo, version: &'static str) -> VersionedAppInfo {
+        VersionedAppInfo {
+            app,
+            version,
+            scoped: &[
+                AppDataType::UserConfig,
+                AppDataType::UserData,
+                AppDataType::SharedData,
+                AppDataType::SharedConfig,
+            ],
+        }
+    }
+
+    /// Returns `true` if directories of the given data type are scoped by
+    /// version.
+    #[must_use]
+    pub fn is_scoped(&self, t: AppDataType) -> bool {
+        self.scoped.contains(&t)
+    }
+}
+
+/// Returns (but **does not create**) path to the **app-specific** data
+/// directory for provided data type, including the version segment if the
+/// data type is scoped.
+///
+/// Like an empty app name or author, an empty version is rejected with
+/// `AppDirsError::InvalidAppInfo`.
+pub fn get_versioned_app_root(
+    t: AppDataType,
+    app: &VersionedAppInfo,
+) -> Result<PathBuf, AppDirsError> {
+    if app.version.is_empty() {
+        return Err(AppDirsError::InvalidAppInfo);
+    }
+    get_app_root(t, &app.app).map(|mut root| {
+        if app.is_scoped(t) {
+            root.push(utils::sanitized(app.version));
+        }
+        root
+    })
+}
+
+/// Creates (if necessary) and returns path to the **app-specific** data
+/// directory for provided data type, including the version segment if the
+/// data type is scoped. (See
+/// [`get_versioned_app_root`](fn.get_versioned_app_root.html).)
+pub fn versioned_app_root(t: AppDataType, app: &VersionedAppInfo) -> Result<PathBuf, AppDirsError> {
+    let path = get_versioned_app_root(t, app)?;
+    create_dir_all(t, path).map_err(ContextError::into_error)
+}
+
+/// Returns (but **does not create**) path to an **app-specific** data
+/// **subdirectory** below the versioned app directory. (See
+/// [`get_versioned_app_root`](fn.get_versioned_app_root.html).)
+///
+/// The `path` parameter should be a valid relative path separated by
+/// **forward slashes** (`/`).
+pub fn get_versioned_app_dir(
+    t: AppDataType,
+    app: &VersionedAppInfo,
+    path: &str,
+) -> Result<PathBuf, AppDirsError> {
+    get_versioned_app_root(t, app).map(|root| push_relative(root, path))
+}
+
+/// Creates (if necessary) and returns path to an **app-specific** data
+/// **subdirectory** below the versioned app directory. (See
+/// [`get_versioned_app_dir`](fn.get_versioned_app_dir.html).)
+pub fn versioned_app_dir(
+    t: AppDataType,
+    app: &VersionedAppInfo,
+    path: &str,
+) -> Result<PathBuf, AppDirsError> {
+    let path = get_versioned_app_dir(t, app, path)?;
+    create_dir_all(t, path).map_err(ContextError::into_error)
+}
+
+/// Lists the version directories that exist below the app directory of the
+/// provided data type, e.g. to offer importing settings from a previous
+/// version.
+///
+/// Every subdirectory of the app directory counts as a version, except the
+/// ones this library uses itself (`profiles` for
+/// [`get_profile_root`](fn.get_profile_root.html) and `tmp` for
+/// [`app_temp_dir`](fn.app_temp_dir.html)), so only use this for data types
+/// that are scoped by version. The names are returned as
+/// they appear on disk (i.e. sanitized), in natural order (`"2.9"` before
+/// `"2.10"`); a missing app directory yields an empty list.
+pub fn list_app_versions(t: AppDataType, app: &AppInfo) -> Result<Vec<String>, AppDirsError> {
+    let root = get_app_root(t, app)?;
+    let entries = match fs::read_dir(&root) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut versions = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            match entry.file_name().into_string() {
+                Ok(ref name) if RESERVED_NAMES.contains(&name.as_str()) => {},
+                Ok(name) => versions.push(name),
+                Err(_) => {},
+            }
+        }
+    }
+    versions.sort_by(|a, b| natural_cmp(a, b));
+    Ok(versions)
+}
+
+/// Compares strings so that runs of digits are ordered by their numeric value.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (a_digits, b_digits) = (digit_prefix(a), digit_prefix(b));
+        let ordering = if !a_digits.is_empty() && !b_digits.is_empty() {
+            let (a_num, b_num) = (
+                a_digits.trim_start_matches('0'),
+                b_digits.trim_start_matches('0'),
+            );
+            a_num
+                .len()
+                .cmp(&b_num.len())
+                .then_with(|| a_num.cmp(b_num))
+                .then_with(|| a_digits.len().cmp(&b_digits.len()))
+        } else {
+            let (a_char, b_char) = (a.chars().next(), b.chars().next());
+            match (a_char, b_char) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                _ => return a_char.is_some().cmp(&b_char.is_some()),
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        let step = |s: &str, digits: &str| {
+            if digits.is_empty() {
+                s.chars().next().map_or(s.len(), char::len_utf8)
+            } else {
+                digits.len()
+            }
+        };
+        let (a_step, b_step) = (step(a, a_digits), step(b, b_digits));
+        a = &a[a_step..];
+        b = &b[b_step..];
+    }
+}
+
+fn digit_prefix(s: &str) -> &str {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    &s[..end]
+}
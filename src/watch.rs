@@ -0,0 +1,296 @@
+use crate::common::{AppDataType, AppDirsError, AppInfo};
+use crate::imp::{get_app_dir, get_app_root};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often [`Worker::rewatch`] sets up the watches again when they fail or
+/// the target appears or disappears meanwhile.
+const REWATCH_ATTEMPTS: usize = 3;
+
+/// A batch of changes below a watched directory, as delivered by
+/// [`AppDirWatcher`](struct.AppDirWatcher.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AppDirEvent {
+    /// The files and directories that changed, sorted and without
+    /// duplicates. The watched directory itself is listed when it was
+    /// created, removed or replaced.
+    pub paths: Vec<PathBuf>,
+}
+
+/// Watches a directory and everything below it, delivering debounced
+/// [`AppDirEvent`](struct.AppDirEvent.html)s, e.g. to hot-reload
+/// configuration.
+///
+/// The directory does not have to exist yet: until it does, its nearest
+/// existing ancestor is watched, and an event is delivered once it is
+/// created. Its parent is watched as well, so the watcher follows the
+/// directory when it is removed, or replaced by renaming another directory
+/// over it.
+///
+/// Changes are collected until none happened for the debounce interval, then
+/// delivered as one event. Watching stops when the `AppDirWatcher` is dropped.
+///
+/// ```no_run
+/// use app_dirs2::*;
+/// use std::time::Duration;
+/// const APP_INFO: AppInfo = AppInfo{name: "CoolApp", author: "SuperDev"};
+/// let watcher = watch_app_root(AppDataType::UserConfig, &APP_INFO, Duration::from_millis(200)).unwrap();
+/// while let Some(event) = watcher.recv() {
+///     println!("reloading after changes to {:?}", event.paths);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AppDirWatcher {
+    path: PathBuf,
+    events: Receiver<AppDirEvent>,
+    control: Sender<Message>,
+    worker: Option<JoinHandle<()>>,
+}
+
+enum Message {
+    Event(notify::Result<Event>),
+    Stop,
+}
+
+impl AppDirWatcher {
+    /// Starts watching the given directory.
+    pub fn new<P: Into<PathBuf>>(
+        path: P,
+        debounce: Duration,
+    ) -> Result<AppDirWatcher, AppDirsError> {
+        let path = normalized(path.into());
+        let (control, messages) = mpsc::channel();
+        let handler = control.clone();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = handler.send(Message::Event(event));
+        })
+        .map_err(watch_error)?;
+        let mut worker = Worker {
+            target: path.clone(),
+            watcher,
+            watched: Vec::new(),
+            existed: path.is_dir(),
+            pending: BTreeSet::new(),
+        };
+        worker.rewatch().map_err(watch_error)?;
+        let (sender, events) = mpsc::channel();
+        let worker = thread::Builder::new()
+            .name("app_dirs2-watch".to_owned())
+            .spawn(move || worker.run(&messages, &sender, debounce))?;
+        Ok(AppDirWatcher {
+            path,
+            events,
+            control,
+            worker: Some(worker),
+        })
+    }
+
+    /// Returns the path of the watched directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Blocks until the next event. Returns `None` if watching stopped.
+    pub fn recv(&self) -> Option<AppDirEvent> {
+        self.events.recv().ok()
+    }
+
+    /// Blocks until the next event or until the timeout elapsed.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<AppDirEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+
+    /// Returns the next event, if one is ready.
+    pub fn try_recv(&self) -> Option<AppDirEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+impl Drop for AppDirWatcher {
+    fn drop(&mut self) {
+        let _ = self.control.send(Message::Stop);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Watches the **app-specific** data directory for provided data type. (See
+/// [`AppDirWatcher`](struct.AppDirWatcher.html).)
+pub fn watch_app_root(
+    t: AppDataType,
+    app: &AppInfo,
+    debounce: Duration,
+) -> Result<AppDirWatcher, AppDirsError> {
+    let path = get_app_root(t, app)?;
+    AppDirWatcher::new(path, debounce)
+}
+
+/// Watches an **app-specific** data **subdirectory** for provided data type.
+/// (See [`AppDirWatcher`](struct.AppDirWatcher.html).)
+///
+/// The `path` parameter should be a valid relative path separated by
+/// **forward slashes** (`/`).
+pub fn watch_app_dir(
+    t: AppDataType,
+    app: &AppInfo,
+    path: &str,
+    debounce: Duration,
+) -> Result<AppDirWatcher, AppDirsError> {
+    let dir = get_app_dir(t, app, path)?;
+    AppDirWatcher::new(dir, debounce)
+}
+
+struct Worker {
+    target: PathBuf,
+    watcher: RecommendedWatcher,
+    watched: Vec<PathBuf>,
+    existed: bool,
+    pending: BTreeSet<PathBuf>,
+}
+
+impl Worker {
+    fn run(
+        mut self,
+        messages: &Receiver<Message>,
+        events: &Sender<AppDirEvent>,
+        debounce: Duration,
+    ) {
+        let mut deadline: Option<Instant> = None;
+        loop {
+            let message = match deadline {
+                None => messages.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                Some(deadline) => {
+                    messages.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                },
+            };
+            match message {
+                Ok(Message::Event(event)) => {
+                    self.handle(event);
+                    if !self.pending.is_empty() {
+                        deadline = Some(Instant::now() + debounce);
+                    }
+                },
+                Err(RecvTimeoutError::Timeout) => {
+                    deadline = None;
+                    let paths = std::mem::take(&mut self.pending).into_iter().collect();
+                    if events.send(AppDirEvent { paths }).is_err() {
+                        return;
+                    }
+                },
+                Ok(Message::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+
+    fn handle(&mut self, event: notify::Result<Event>) {
+        let mut moved = false;
+        match event {
+            // Opening or reading files changes nothing. Setting up the
+            // watches opens the directories, so reacting to this would
+            // rewatch forever.
+            Ok(ref event) if event.kind.is_access() => return,
+            Ok(event) => {
+                if event.need_rescan() {
+                    moved = true;
+                }
+                for path in event.paths {
+                    if path.starts_with(&self.target) {
+                        self.pending.insert(path);
+                    } else if self.target.starts_with(&path) {
+                        moved = true;
+                    }
+                }
+            },
+            // The backend lost track; start over.
+            Err(_) => moved = true,
+        }
+        if moved || self.pending.contains(&self.target) {
+            // An ancestor stays watched even if watching the target fails,
+            // so this is retried on the ancestor's next change.
+            let _ = self.rewatch();
+        }
+    }
+
+    /// Watches the target recursively and its parent non-recursively, or
+    /// only its nearest existing ancestor if it does not exist.
+    ///
+    /// Watching the target fails if it (or a directory below it) is removed
+    /// while the watches are set up, e.g. when an editor replaces it. The
+    /// nearest ancestor that can be watched is watched regardless, so at
+    /// least one watch stays active.
+    fn rewatch(&mut self) -> notify::Result<()> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            for path in self.watched.drain(..) {
+                let _ = self.watcher.unwatch(&path);
+            }
+            let exists = self.target.is_dir();
+            let mut result = Ok(());
+            let parent = if exists {
+                match self.watcher.watch(&self.target, RecursiveMode::Recursive) {
+                    Ok(()) => self.watched.push(self.target.clone()),
+                    Err(e) => {
+                        // Drop whatever part of the recursive watch was set up.
+                        let _ = self.watcher.unwatch(&self.target);
+                        result = Err(e);
+                    },
+                }
+                self.target.parent()
+            } else {
+                Some(self.target.as_path())
+            };
+            let mut ancestor_result = Ok(());
+            for ancestor in parent.into_iter().flat_map(Path::ancestors) {
+                if !ancestor.is_dir() {
+                    continue;
+                }
+                // The ancestor may vanish, too; then try the next one.
+                ancestor_result = self.watcher.watch(ancestor, RecursiveMode::NonRecursive);
+                if ancestor_result.is_ok() {
+                    self.watched.push(ancestor.to_owned());
+                    break;
+                }
+            }
+            let result = result.and(ancestor_result);
+            if exists != self.existed {
+                self.existed = exists;
+                self.pending.insert(self.target.clone());
+            }
+            // Directories created or removed while the watches were being
+            // set up went unnoticed; look again.
+            if (result.is_ok() && self.target.is_dir() == exists) || attempts == REWATCH_ATTEMPTS {
+                return result;
+            }
+        }
+    }
+}
+
+/// Resolves symlinks in the nearest existing ancestor, so the path matches
+/// the paths reported by the backend.
+fn normalized(path: PathBuf) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            return match path.strip_prefix(ancestor) {
+                Ok(rest) if !rest.as_os_str().is_empty() => canonical.join(rest),
+                _ => canonical,
+            };
+        }
+    }
+    path
+}
+
+fn watch_error(e: notify::Error) -> AppDirsError {
+    match e.kind {
+        notify::ErrorKind::Io(e) => e.into(),
+        _ => Error::other(e).into(),
+    }
+}
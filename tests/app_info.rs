@@ -0,0 +1,116 @@
+use app_dirs2::{app_info, AppInfo};
+use test_case::test_case;
+
+const APP_INFO: AppInfo = app_info!();
+
+#[test]
+fn test_app_info_from_package() {
+    assert_eq!("app_dirs2", APP_INFO.name);
+    assert_eq!("Andy Barron", APP_INFO.author);
+}
+
+#[test]
+fn test_app_info_overrides() {
+    const NAMED: AppInfo = app_info!(name = "CoolApp");
+    assert_eq!(("CoolApp", "Andy Barron"), (NAMED.name, NAMED.author));
+    const AUTHORED: AppInfo = app_info!(author = "SuperDev",);
+    assert_eq!(("app_dirs2", "SuperDev"), (AUTHORED.name, AUTHORED.author));
+    const BOTH: AppInfo = app_info!(author = "SuperDev", name = "CoolApp");
+    assert_eq!(("CoolApp", "SuperDev"), (BOTH.name, BOTH.author));
+}
+
+#[test]
+fn test_first_author() {
+    use app_dirs2::__first_author as first_author;
+    assert_eq!("A B", first_author("A B <a@b.c>:C <c@d.e>", "x"));
+    assert_eq!("A", first_author("A:C", "x"));
+    assert_eq!("Ünïcode", first_author("Ünïcode", "x"));
+    assert_eq!("x", first_author("", "x"));
+    assert_eq!("x", first_author("<a@b.c>", "x"));
+}
+
+#[test]
+fn test_app_qualifier() {
+    const QUALIFIER: Option<&str> = app_dirs2::app_qualifier!();
+    assert_eq!(None, QUALIFIER);
+}
+
+const MANIFEST: &str = r#"[package]
+name = "cool-app"
+display-name = "Not This"
+
+[package.metadata.app_dirs2]
+# The name shown to users
+display-name = "Cool App"  # trailing comment
+qualifier='com.example'
+qualifiers = "nope"
+
+[ package.metadata.app_dirs2.extra ]
+other = "x"
+"#;
+
+#[test]
+fn test_package_metadata() {
+    use app_dirs2::__package_metadata as metadata;
+    assert_eq!(Some("Cool App"), metadata(MANIFEST, "display-name"));
+    assert_eq!(Some("com.example"), metadata(MANIFEST, "qualifier"));
+    assert_eq!(None, metadata(MANIFEST, "other"));
+    assert_eq!(None, metadata(MANIFEST, "name"));
+    assert_eq!(None, metadata("", "qualifier"));
+    let crlf = "[ package.metadata.app_dirs2 ]\r\nqualifier = \"org\"\r\n";
+    assert_eq!(Some("org"), metadata(crlf, "qualifier"));
+}
+
+const TRICKY: &str = r#"[package]
+name = "cool-app"
+description = """
+Not metadata:
+[package.metadata.app_dirs2]
+qualifier = "org.fake"
+"""
+keywords = ["a]", 'b"', """c
+"""]
+metadata.app_dirs2.display-name = "Dotted"  # comment with """
+homepage = '''
+[package.metadata.app_dirs2]'''
+published = 1979-05-27 07:32:00Z
+
+[[bin]]
+qualifier = "not.this"
+
+[package.metadata]
+app_dirs2 = { "qualifier" = 'org.inline', other = [1, { x = "}" }] }
+"#;
+
+#[test]
+fn test_package_metadata_tricky() {
+    use app_dirs2::__package_metadata as metadata;
+    assert_eq!(Some("Dotted"), metadata(TRICKY, "display-name"));
+    assert_eq!(Some("org.inline"), metadata(TRICKY, "qualifier"));
+    assert_eq!(None, metadata(TRICKY, "other"));
+    let quoted = "[\"package\".'metadata'.app_dirs2]\n'qualifier' = \"org\"";
+    assert_eq!(Some("org"), metadata(quoted, "qualifier"));
+    let top = "package . metadata . app_dirs2 . qualifier = \"top\"\n[package]";
+    assert_eq!(Some("top"), metadata(top, "qualifier"));
+}
+
+macro_rules! qualifier_table {
+    ($value:literal) => {
+        concat!("[package.metadata.app_dirs2]\nqualifier = ", $value, "\n")
+    };
+}
+
+#[test_case(qualifier_table!("\"a\\\"b\""); "escape")]
+#[test_case(qualifier_table!("1"); "not string")]
+#[test_case(qualifier_table!("\"\"\"a\"\"\""); "multi-line")]
+#[test_case(qualifier_table!("'''a'''"); "multi-line literal")]
+#[test_case(qualifier_table!("\"unterminated"); "unterminated")]
+#[test_case(qualifier_table!("[\"a\"]"); "array")]
+#[test_case(qualifier_table!(""); "missing")]
+#[test_case("[package.metadata.app_dirs2"; "unterminated header")]
+#[test_case("[package]\nmetadata = {"; "unterminated table")]
+#[test_case("x = [1 2]\n"; "malformed array")]
+#[test_case("\"qualifier"; "unterminated key")]
+fn test_package_metadata_unreadable(manifest: &'static str) {
+    assert_eq!(None, app_dirs2::__package_metadata(manifest, "qualifier"));
+}
@@ -0,0 +1,112 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::fs;
+use std::io::ErrorKind;
+use std::os::unix::fs::PermissionsExt;
+
+use app_dirs2::{AppDataType, AppDirsError, AppInfo, BackupManifest, BackupOptions};
+use tempfile::TempDir;
+use test_case::test_case;
+
+mod common;
+use common::{set_xdg_roots, APP_INFO, ENV_MUTEX};
+
+// Fills the app's directories below a new root and backs them up.
+fn backup(gzip: bool) -> (TempDir, Vec<u8>, BackupManifest) {
+    let old = tempfile::tempdir().unwrap();
+    set_xdg_roots(old.path());
+    let config = app_dirs2::app_dir(AppDataType::UserConfig, &APP_INFO, "sub").unwrap();
+    fs::write(config.join("settings"), b"a = 1").unwrap();
+    let data = app_dirs2::app_root(AppDataType::UserData, &APP_INFO).unwrap();
+    fs::write(data.join("db"), b"rows").unwrap();
+    fs::set_permissions(data.join("db"), fs::Permissions::from_mode(0o600)).unwrap();
+    let thumbnails = app_dirs2::app_dir(AppDataType::UserData, &APP_INFO, "thumbnails").unwrap();
+    fs::write(
+        thumbnails.join("CACHEDIR.TAG"),
+        b"Signature: 8a477f597d28d172789f06886806bc55\n",
+    )
+    .unwrap();
+    let cache = app_dirs2::app_root(AppDataType::UserCache, &APP_INFO).unwrap();
+    fs::write(cache.join("blob"), b"cached").unwrap();
+    // Shared directories are left out by default.
+    let shared = app_dirs2::app_root(AppDataType::SharedConfig, &APP_INFO).unwrap();
+    fs::write(shared.join("defaults"), b"a = 0").unwrap();
+
+    let mut archive = Vec::new();
+    let mut options = BackupOptions::default();
+    options.gzip = gzip;
+    let manifest = app_dirs2::backup_app_dirs(&APP_INFO, &mut archive, &options).unwrap();
+    (old, archive, manifest)
+}
+
+#[test_case(false; "tar")]
+#[test_case(true; "gzip")]
+fn test_backup(gzip: bool) {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let (_old, archive, manifest) = backup(gzip);
+    assert_eq!("app-name", manifest.app_name);
+    assert_eq!(2, manifest.roots.len());
+    assert_eq!(vec![AppDataType::UserConfig], manifest.roots[0].data_types);
+    assert_eq!(vec!["sub", "sub/settings"], manifest.roots[0].paths);
+    assert_eq!(vec![AppDataType::UserData], manifest.roots[1].data_types);
+    assert_eq!(vec!["db"], manifest.roots[1].paths);
+    assert_eq!(gzip, archive.starts_with(&[0x1f, 0x8b]));
+}
+
+#[test_case(false; "tar")]
+#[test_case(true; "gzip")]
+fn test_restore(gzip: bool) {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let (old, archive, manifest) = backup(gzip);
+    let new = tempfile::tempdir().unwrap();
+    set_xdg_roots(new.path());
+    let restored = app_dirs2::restore_app_dirs(&APP_INFO, &archive[..]).unwrap();
+    assert_eq!(manifest, restored);
+    let root = new.path();
+    assert_eq!(
+        b"a = 1",
+        &fs::read(root.join("config/app-name/sub/settings")).unwrap()[..]
+    );
+    assert_eq!(
+        b"rows",
+        &fs::read(root.join("data/app-name/db")).unwrap()[..]
+    );
+    assert!(!root.join("data/app-name/thumbnails").exists());
+    assert!(!root.join("cache").exists());
+
+    let mode = fs::metadata(root.join("data/app-name/db"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(0o600, mode & 0o777);
+    // Other files keep their mode as well.
+    assert_eq!(
+        fs::metadata(old.path().join("config/app-name/sub/settings"))
+            .unwrap()
+            .permissions(),
+        fs::metadata(root.join("config/app-name/sub/settings"))
+            .unwrap()
+            .permissions()
+    );
+}
+
+#[test]
+fn test_restore_other_app() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let (_old, archive, _) = backup(false);
+    let new = tempfile::tempdir().unwrap();
+    set_xdg_roots(new.path());
+    let other = AppInfo {
+        name: "other-app",
+        author: "app-author",
+    };
+    let e = app_dirs2::restore_app_dirs(&other, &archive[..]).unwrap_err();
+    match e {
+        AppDirsError::Io(e) => assert_eq!(ErrorKind::InvalidData, e.kind()),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(!new.path().join("config").exists());
+}
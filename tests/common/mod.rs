@@ -0,0 +1,44 @@
+//! Fixtures shared by the integration tests that point the XDG environment
+//! variables at temporary directories.
+
+// Every test file uses a different subset.
+#![allow(dead_code)]
+
+use std::env;
+use std::path::Path;
+use std::sync;
+
+use app_dirs2::AppInfo;
+use once_cell::sync::Lazy;
+
+// For test cases that depend on environment variables
+pub static ENV_MUTEX: Lazy<sync::Mutex<()>> = Lazy::new(|| sync::Mutex::new(()));
+
+pub const APP_INFO: AppInfo = AppInfo {
+    name: "app-name",
+    author: "app-author",
+};
+
+/// Points `$HOME` and the XDG variables at directories below `root`: `home`,
+/// `config`, `data` and `cache` for the user-specific data types and
+/// `shared` for both shared ones.
+pub fn set_xdg_roots(root: &Path) {
+    env::set_var("HOME", root.join("home"));
+    env::set_var("XDG_CONFIG_HOME", root.join("config"));
+    env::set_var("XDG_DATA_HOME", root.join("data"));
+    env::set_var("XDG_CACHE_HOME", root.join("cache"));
+    env::set_var("XDG_DATA_DIRS", root.join("shared"));
+    env::set_var("XDG_CONFIG_DIRS", root.join("shared"));
+}
+
+/// Like [`set_xdg_roots`], but points `UserConfig` at `user` and
+/// `XDG_CONFIG_DIRS` at `site` followed by `vendor`, for tests of the
+/// configuration search path.
+pub fn set_config_search_path(root: &Path) {
+    set_xdg_roots(root);
+    env::set_var("XDG_CONFIG_HOME", root.join("user"));
+    env::set_var(
+        "XDG_CONFIG_DIRS",
+        env::join_paths([root.join("site"), root.join("vendor")]).unwrap(),
+    );
+}
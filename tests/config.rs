@@ -0,0 +1,119 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::fs;
+use std::path::Path;
+
+use app_dirs2::AppDataType;
+
+mod common;
+use common::{set_config_search_path, APP_INFO, ENV_MUTEX};
+
+fn write(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn test_search_path() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    set_config_search_path(root);
+
+    assert_eq!(
+        vec![
+            root.join("vendor/app-name"),
+            root.join("site/app-name"),
+            root.join("user/app-name"),
+        ],
+        app_dirs2::get_app_search_path(AppDataType::UserConfig, &APP_INFO).unwrap()
+    );
+    assert_eq!(
+        vec![root.join("vendor"), root.join("site")],
+        app_dirs2::get_data_search_path(AppDataType::SharedConfig).unwrap()
+    );
+}
+
+#[test]
+fn test_no_files() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_config_search_path(dir.path());
+
+    let empty = app_dirs2::load_toml_config(&APP_INFO, "config.toml").unwrap();
+    assert_eq!(toml::Value::Table(toml::Table::new()), empty.value);
+    assert!(empty.files.is_empty());
+}
+
+#[test]
+fn test_toml() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    set_config_search_path(root);
+
+    let vendor = root.join("vendor/app-name/config.toml");
+    let user = root.join("user/app-name/config.toml");
+    write(
+        &vendor,
+        "[ui]\ntheme = \"light\"\nfont = \"mono\"\n[plugins]\nenabled = [\"a\", \"b\"]\n[net]\nproxy = { host = \"x\" }\n",
+    );
+    write(
+        &user,
+        "[ui]\ntheme = \"dark\"\n[plugins]\nenabled = [\"c\"]\n[net]\nproxy = \"none\"\n",
+    );
+    let config = app_dirs2::load_toml_config(&APP_INFO, "config.toml").unwrap();
+    assert_eq!(vec![vendor.clone(), user.clone()], config.files);
+    let expected: toml::Table =
+        "[ui]\ntheme = \"dark\"\nfont = \"mono\"\n[plugins]\nenabled = [\"c\"]\n[net]\nproxy = \"none\"\n"
+            .parse()
+            .unwrap();
+    assert_eq!(toml::Value::Table(expected), config.value);
+    assert_eq!(Some(user.as_path()), config.source_of(&["ui", "theme"]));
+    assert_eq!(Some(vendor.as_path()), config.source_of(&["ui", "font"]));
+    assert_eq!(
+        Some(user.as_path()),
+        config.source_of(&["plugins", "enabled"])
+    );
+    assert_eq!(Some(user.as_path()), config.source_of(&["net", "proxy"]));
+    assert_eq!(None, config.source_of(&["net", "proxy", "host"]));
+    assert_eq!(None, config.source_of(&["ui"]));
+}
+
+#[test]
+fn test_json() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    set_config_search_path(root);
+
+    let site = root.join("site/app-name/settings.json");
+    write(&site, r#"{"a": {"b": 1, "c": 2}}"#);
+    write(
+        &root.join("user/app-name/settings.json"),
+        r#"{"a": {"c": 3}}"#,
+    );
+    let config = app_dirs2::load_json_config(&APP_INFO, "settings.json").unwrap();
+    assert_eq!(serde_json::json!({"a": {"b": 1, "c": 3}}), config.value);
+    assert_eq!(Some(site.as_path()), config.source_of(&["a", "b"]));
+}
+
+#[test]
+fn test_invalid_file() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    set_config_search_path(root);
+
+    write(&root.join("user/app-name/bad.json"), "[1, 2]");
+    let e = app_dirs2::load_json_config(&APP_INFO, "bad.json").unwrap_err();
+    match e {
+        app_dirs2::AppDirsError::Io(e) => assert_eq!(std::io::ErrorKind::InvalidData, e.kind()),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
@@ -0,0 +1,37 @@
+use app_dirs2::AppDataType;
+use test_case::test_case;
+
+#[test_case(AppDataType::UserConfig, "user-config"; "user config")]
+#[test_case(AppDataType::UserData, "user-data"; "user data")]
+#[test_case(AppDataType::UserCache, "user-cache"; "user cache")]
+#[test_case(AppDataType::SharedData, "shared-data"; "shared data")]
+#[test_case(AppDataType::SharedConfig, "shared-config"; "shared config")]
+fn test_display_round_trip(ty: AppDataType, name: &str) {
+    assert_eq!(name, ty.to_string());
+    assert_eq!(Ok(ty), name.parse());
+}
+
+#[test_case("config", AppDataType::UserConfig; "config")]
+#[test_case("Cache", AppDataType::UserCache; "cache")]
+#[test_case("XDG_DATA", AppDataType::UserData; "xdg data")]
+#[test_case("SharedConfig", AppDataType::SharedConfig; "variant name")]
+#[test_case("shared_data", AppDataType::SharedData; "snake case")]
+fn test_aliases(input: &str, ty: AppDataType) {
+    assert_eq!(Ok(ty), input.parse());
+}
+
+#[test]
+fn test_parse_error() {
+    let err = "user-state".parse::<AppDataType>().unwrap_err();
+    assert_eq!("user-state", err.input());
+    assert!(err.to_string().contains("user-config"));
+}
+
+#[test]
+fn test_all() {
+    let all: Vec<_> = AppDataType::all().collect();
+    assert_eq!(5, all.len());
+    for ty in all {
+        assert_eq!(Ok(ty), ty.name().parse());
+    }
+}
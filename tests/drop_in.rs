@@ -0,0 +1,85 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+use app_dirs2::AppDataType;
+
+mod common;
+use common::{set_config_search_path, APP_INFO, ENV_MUTEX};
+
+/// Creates the `conf.d` directories below the given root with overlapping
+/// fragments, returning the vendor, site and user directories.
+fn populate(root: &Path) -> (PathBuf, PathBuf, PathBuf) {
+    let vendor = root.join("vendor/app-name/conf.d");
+    let site = root.join("site/app-name/conf.d");
+    let user = root.join("user/app-name/conf.d");
+    for dir in &[&vendor, &site, &user] {
+        fs::create_dir_all(dir).unwrap();
+    }
+    fs::write(vendor.join("10-base.conf"), b"a").unwrap();
+    fs::write(vendor.join("20-net.conf"), b"a").unwrap();
+    fs::write(vendor.join("30-ui.conf"), b"a").unwrap();
+    fs::write(vendor.join("40-debug.conf"), b"a").unwrap();
+    fs::create_dir(vendor.join("50-not-a-file.conf")).unwrap();
+    fs::write(site.join("20-net.conf"), b"b").unwrap();
+    fs::write(site.join("30-ui.conf"), b"").unwrap();
+    fs::write(site.join("05-early.conf"), b"b").unwrap();
+    fs::write(user.join("20-net.conf"), b"c").unwrap();
+    symlink("/dev/null", user.join("40-debug.conf")).unwrap();
+    (vendor, site, user)
+}
+
+#[test]
+fn test_missing_directories() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_config_search_path(dir.path());
+
+    assert!(
+        app_dirs2::drop_in_fragments(AppDataType::UserConfig, &APP_INFO, "conf.d")
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_user_fragments() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_config_search_path(dir.path());
+    let (vendor, site, user) = populate(dir.path());
+
+    // 30-ui.conf is masked by an empty file, 40-debug.conf by a symlink to
+    // /dev/null.
+    assert_eq!(
+        vec![
+            site.join("05-early.conf"),
+            vendor.join("10-base.conf"),
+            user.join("20-net.conf"),
+        ],
+        app_dirs2::drop_in_fragments(AppDataType::UserConfig, &APP_INFO, "conf.d").unwrap()
+    );
+}
+
+#[test]
+fn test_shared_fragments() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_config_search_path(dir.path());
+    let (vendor, site, _) = populate(dir.path());
+
+    assert_eq!(
+        vec![
+            site.join("05-early.conf"),
+            vendor.join("10-base.conf"),
+            site.join("20-net.conf"),
+            vendor.join("40-debug.conf"),
+        ],
+        app_dirs2::drop_in_fragments(AppDataType::SharedConfig, &APP_INFO, "conf.d").unwrap()
+    );
+}
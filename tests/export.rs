@@ -0,0 +1,73 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::env;
+use std::ffi;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use app_dirs2::ExportFormat;
+use test_case::test_case;
+
+mod common;
+use common::{APP_INFO, ENV_MUTEX};
+
+fn set_env(config_home: &[u8]) {
+    common::set_xdg_roots(Path::new("/root"));
+    env::set_var("XDG_CONFIG_HOME", ffi::OsStr::from_bytes(config_home));
+}
+
+#[test_case(ExportFormat::Sh, "export APP_USER_CONFIG='/it'\\''s \"here\"/app-name'"; "sh")]
+#[test_case(ExportFormat::Fish, "set -gx APP_USER_CONFIG '/it\\'s \"here\"/app-name'"; "fish")]
+#[test_case(ExportFormat::PowerShell, "$env:APP_USER_CONFIG = '/it''s \"here\"/app-name'"; "powershell")]
+#[test_case(ExportFormat::Dotenv, "APP_USER_CONFIG=\"/it's \\\"here\\\"/app-name\""; "dotenv")]
+fn test_special_characters(format: ExportFormat, expected: &str) {
+    let _env_guard = ENV_MUTEX.lock();
+    set_env(b"/it's \"here\"");
+
+    let exports = app_dirs2::export_app_roots(&APP_INFO, format, "APP_").unwrap();
+    let lines: Vec<_> = exports.lines().collect();
+    assert_eq!(5, lines.len());
+    assert_eq!(expected, lines[0]);
+}
+
+#[test_case(ExportFormat::Sh, Some("export APP_USER_CONFIG='/a'\"$(printf '\\377')\"'b/app-name'"); "sh")]
+#[test_case(ExportFormat::Fish, Some("set -gx APP_USER_CONFIG '/a'\\xff'b/app-name'"); "fish")]
+#[test_case(ExportFormat::PowerShell, None; "powershell")]
+#[test_case(ExportFormat::Dotenv, None; "dotenv")]
+fn test_non_utf8(format: ExportFormat, expected: Option<&str>) {
+    let _env_guard = ENV_MUTEX.lock();
+    set_env(b"/a\xffb");
+
+    let exports = app_dirs2::export_app_roots(&APP_INFO, format, "APP_");
+    match expected {
+        Some(expected) => assert_eq!(expected, exports.unwrap().lines().next().unwrap()),
+        None => assert!(exports.is_err()),
+    }
+}
+
+#[test_case("MY APP_"; "space")]
+#[test_case("X;rm -rf ~;"; "injection")]
+#[test_case("1APP_"; "leading digit")]
+#[test_case("APP-"; "hyphen")]
+fn test_invalid_prefix(prefix: &str) {
+    let _env_guard = ENV_MUTEX.lock();
+    set_env(b"/config");
+
+    let err = app_dirs2::export_app_roots(&APP_INFO, ExportFormat::Sh, prefix).unwrap_err();
+    match err {
+        app_dirs2::AppDirsError::Io(e) => assert_eq!(std::io::ErrorKind::InvalidInput, e.kind()),
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test_case(""; "empty")]
+#[test_case("_"; "underscore")]
+#[test_case("App_2_"; "mixed")]
+fn test_valid_prefix(prefix: &str) {
+    let _env_guard = ENV_MUTEX.lock();
+    set_env(b"/config");
+
+    let exports = app_dirs2::export_app_roots(&APP_INFO, ExportFormat::Sh, prefix).unwrap();
+    let expected = format!("export {}USER_CONFIG='/config/app-name'", prefix);
+    assert_eq!(expected, exports.lines().next().unwrap());
+}
@@ -124,3 +124,30 @@ fn test_create(ty: AppDataType) -> io::Result<()> {
 
     dir.close()
 }
+
+#[test]
+fn test_create_error_context() -> io::Result<()> {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir()?;
+    let root_dir = set_root_dir(dir.path());
+    // Make the home directory a regular file so nothing can be created in it.
+    std::fs::create_dir_all(&root_dir)?;
+    std::fs::write(root_dir.join("home"), b"")?;
+
+    let info = app_dirs2::AppInfo {
+        name: "test-app",
+        author: "test-author",
+    };
+
+    let app_root = app_dirs2::get_app_root(AppDataType::UserConfig, &info).unwrap();
+    let err = app_dirs2::app_root(AppDataType::UserConfig, &info).unwrap_err();
+    assert!(matches!(err, app_dirs2::AppDirsError::Io(_)));
+    let err = app_dirs2::app_root_with_context(AppDataType::UserConfig, &info).unwrap_err();
+    assert!(matches!(err.error(), app_dirs2::AppDirsError::Io(_)));
+    let context = err.context();
+    assert_eq!(Some(AppDataType::UserConfig), context.data_type);
+    assert_eq!(Some(app_root), context.path);
+
+    dir.close()
+}
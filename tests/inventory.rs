@@ -0,0 +1,133 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::env;
+use std::fs;
+use std::path;
+
+use app_dirs2::AppDataType;
+
+mod common;
+use common::{APP_INFO, ENV_MUTEX};
+
+// UserData shares its directory with UserConfig, and SharedData with
+// SharedConfig.
+// Makes UserData alias UserConfig.
+fn set_roots(root: &path::Path) {
+    common::set_xdg_roots(root);
+    env::set_var("XDG_DATA_HOME", root.join("config"));
+}
+
+#[test]
+fn test_inventory() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    set_roots(root);
+
+    let config = app_dirs2::app_dir(AppDataType::UserConfig, &APP_INFO, "sub").unwrap();
+    fs::write(config.join("a"), b"12345").unwrap();
+    fs::write(config.join("../b"), b"123").unwrap();
+    app_dirs2::app_root(AppDataType::UserCache, &APP_INFO).unwrap();
+
+    let inventory = app_dirs2::app_inventory(&APP_INFO).unwrap();
+    assert_eq!(3, inventory.len());
+
+    assert_eq!(root.join("config/app-name"), inventory[0].path);
+    assert_eq!(
+        vec![AppDataType::UserConfig, AppDataType::UserData],
+        inventory[0].data_types
+    );
+    assert!(inventory[0].exists);
+    assert_eq!((8, 2), (inventory[0].size, inventory[0].file_count));
+
+    assert_eq!(root.join("cache/app-name"), inventory[1].path);
+    assert_eq!(vec![AppDataType::UserCache], inventory[1].data_types);
+    assert!(inventory[1].exists);
+    assert_eq!((0, 0), (inventory[1].size, inventory[1].file_count));
+
+    assert_eq!(root.join("shared/app-name"), inventory[2].path);
+    assert_eq!(
+        vec![AppDataType::SharedData, AppDataType::SharedConfig],
+        inventory[2].data_types
+    );
+    assert!(!inventory[2].exists);
+}
+
+#[test]
+fn test_aliases() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_roots(dir.path());
+
+    assert_eq!(
+        vec![
+            vec![AppDataType::UserConfig, AppDataType::UserData],
+            vec![AppDataType::SharedData, AppDataType::SharedConfig],
+        ],
+        app_dirs2::data_type_aliases().unwrap()
+    );
+    assert_eq!(
+        vec![AppDataType::UserConfig],
+        app_dirs2::aliases_of(AppDataType::UserData).unwrap()
+    );
+    assert!(app_dirs2::aliases_of(AppDataType::UserCache)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_symlinked_aliases() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    set_roots(root);
+    fs::create_dir(root.join("config")).unwrap();
+    std::os::unix::fs::symlink(root.join("config"), root.join("cache-link")).unwrap();
+    env::set_var("XDG_CACHE_HOME", root.join("cache-link"));
+
+    assert_eq!(
+        vec![AppDataType::UserConfig, AppDataType::UserData],
+        app_dirs2::aliases_of(AppDataType::UserCache).unwrap()
+    );
+}
+
+#[test]
+fn test_nested_data_types() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    set_roots(root);
+    assert!(app_dirs2::nested_data_types().unwrap().is_empty());
+
+    env::set_var("XDG_CACHE_HOME", root.join("config/cache"));
+    assert_eq!(
+        vec![
+            (AppDataType::UserConfig, AppDataType::UserCache),
+            (AppDataType::UserData, AppDataType::UserCache),
+        ],
+        app_dirs2::nested_data_types().unwrap()
+    );
+    // Nesting is not aliasing.
+    assert!(app_dirs2::aliases_of(AppDataType::UserCache)
+        .unwrap()
+        .is_empty());
+
+    // Nested through a symlink.
+    fs::create_dir_all(root.join("config/cache")).unwrap();
+    std::os::unix::fs::symlink(root.join("config/cache"), root.join("cache-link")).unwrap();
+    env::set_var("XDG_CACHE_HOME", root.join("cache-link"));
+    env::set_var("XDG_DATA_HOME", root.join("cache-link/data"));
+    fs::create_dir_all(root.join("cache-link/data")).unwrap();
+    assert_eq!(
+        vec![
+            (AppDataType::UserConfig, AppDataType::UserData),
+            (AppDataType::UserConfig, AppDataType::UserCache),
+            (AppDataType::UserCache, AppDataType::UserData),
+        ],
+        app_dirs2::nested_data_types().unwrap()
+    );
+}
@@ -0,0 +1,87 @@
+use app_dirs2::{target_app_dir, target_app_root, target_data_root};
+use app_dirs2::{AppDataType, AppDirsError, AppInfo, TargetDirs, TargetPlatform};
+use test_case::test_case;
+
+const APP_INFO: AppInfo = AppInfo {
+    name: "Cool App",
+    author: "Super/Dev",
+};
+
+fn target_dirs() -> TargetDirs {
+    let mut dirs = TargetDirs::default();
+    dirs.home = Some("/home/rusty".into());
+    dirs.roaming_app_data = Some(r"C:\Users\Rusty\AppData\Roaming".into());
+    dirs.local_app_data = Some(r"C:\Users\Rusty\AppData\Local".into());
+    dirs.program_data = Some(r"C:\ProgramData".into());
+    dirs.android_data_dir = Some("/data/user/0/org.example".into());
+    dirs.android_files_dir = Some("/data/user/0/org.example/files".into());
+    dirs.android_cache_dir = Some("/data/user/0/org.example/cache".into());
+    dirs.android_external_files_dir =
+        Some("/storage/emulated/0/Android/data/org.example/files".into());
+    dirs
+}
+
+#[test_case(TargetPlatform::Windows, AppDataType::UserConfig, r"C:\Users\Rusty\AppData\Roaming\Super,47,Dev\Cool App"; "windows user config")]
+#[test_case(TargetPlatform::Windows, AppDataType::UserData, r"C:\Users\Rusty\AppData\Local\Super,47,Dev\Cool App"; "windows user data")]
+#[test_case(TargetPlatform::Windows, AppDataType::UserCache, r"C:\Users\Rusty\AppData\Local\Super,47,Dev\Cool App"; "windows user cache")]
+#[test_case(TargetPlatform::Windows, AppDataType::SharedData, r"C:\ProgramData\Super,47,Dev\Cool App"; "windows shared data")]
+#[test_case(TargetPlatform::Windows, AppDataType::SharedConfig, r"C:\ProgramData\Super,47,Dev\Cool App"; "windows shared config")]
+#[test_case(TargetPlatform::MacOs, AppDataType::UserConfig, "/home/rusty/Library/Application Support/Cool App"; "macos user config")]
+#[test_case(TargetPlatform::MacOs, AppDataType::UserData, "/home/rusty/Library/Application Support/Cool App"; "macos user data")]
+#[test_case(TargetPlatform::MacOs, AppDataType::UserCache, "/home/rusty/Library/Caches/Cool App"; "macos user cache")]
+#[test_case(TargetPlatform::MacOs, AppDataType::SharedData, "/Library/Application Support/Cool App"; "macos shared data")]
+#[test_case(TargetPlatform::MacOs, AppDataType::SharedConfig, "/Library/Application Support/Cool App"; "macos shared config")]
+#[test_case(TargetPlatform::Unix, AppDataType::UserConfig, "/home/rusty/.config/Cool App"; "unix user config")]
+#[test_case(TargetPlatform::Unix, AppDataType::UserData, "/home/rusty/.local/share/Cool App"; "unix user data")]
+#[test_case(TargetPlatform::Unix, AppDataType::UserCache, "/home/rusty/.cache/Cool App"; "unix user cache")]
+#[test_case(TargetPlatform::Unix, AppDataType::SharedData, "/usr/local/share/Cool App"; "unix shared data")]
+#[test_case(TargetPlatform::Unix, AppDataType::SharedConfig, "/etc/xdg/Cool App"; "unix shared config")]
+#[test_case(TargetPlatform::Android, AppDataType::UserConfig, "/data/user/0/org.example/Cool App"; "android user config")]
+#[test_case(TargetPlatform::Android, AppDataType::UserData, "/data/user/0/org.example/files/Cool App"; "android user data")]
+#[test_case(TargetPlatform::Android, AppDataType::UserCache, "/data/user/0/org.example/cache/Cool App"; "android user cache")]
+#[test_case(TargetPlatform::Android, AppDataType::SharedData, "/storage/emulated/0/Android/data/org.example/files/Cool App"; "android shared data")]
+#[test_case(TargetPlatform::Android, AppDataType::SharedConfig, "/storage/emulated/0/Android/data/org.example/files/Cool App"; "android shared config")]
+fn test_app_root(target: TargetPlatform, ty: AppDataType, expected: &str) {
+    let app_root = target_app_root(target, ty, &APP_INFO, &target_dirs()).unwrap();
+    assert_eq!(expected, app_root);
+    assert!(app_root.starts_with(&target_data_root(target, ty, &target_dirs()).unwrap()));
+
+    let app_dir = target_app_dir(target, ty, &APP_INFO, "a/b", &target_dirs()).unwrap();
+    let sep = target.separator();
+    assert_eq!(format!("{}{}a{}b", expected, sep, sep), app_dir);
+}
+
+#[test]
+fn test_xdg_vars() {
+    let mut dirs = TargetDirs::default();
+    dirs.xdg_config_home = Some("/xdg/config".into());
+    dirs.xdg_data_home = Some("relative".into());
+    dirs.xdg_data_dirs = Some("relative:/xdg/data:/xdg/data2".into());
+    let root = |ty| target_data_root(TargetPlatform::Unix, ty, &dirs);
+
+    assert_eq!("/xdg/config", root(AppDataType::UserConfig).unwrap());
+    assert_eq!("/xdg/data", root(AppDataType::SharedData).unwrap());
+    assert_eq!("/etc/xdg", root(AppDataType::SharedConfig).unwrap());
+    // Relative values are ignored, and there is no home directory to fall
+    // back to.
+    let err = root(AppDataType::UserData).unwrap_err();
+    assert!(matches!(err.error(), AppDirsError::NotSupported));
+    assert_eq!(Some("HOME"), err.context().env_var);
+}
+
+#[test]
+fn test_missing_input() {
+    let err = target_data_root(
+        TargetPlatform::Windows,
+        AppDataType::UserConfig,
+        &TargetDirs::default(),
+    )
+    .unwrap_err();
+    let context = err.context();
+    assert_eq!(Some(AppDataType::UserConfig), context.data_type);
+    assert_eq!(Some("windows"), context.backend);
+    assert_eq!(
+        Some("SHGetKnownFolderPath(FOLDERID_RoamingAppData)"),
+        context.api_call
+    );
+}
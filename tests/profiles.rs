@@ -0,0 +1,115 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path;
+
+use app_dirs2::{AppDataType, AppDirsError};
+use test_case::test_case;
+
+mod common;
+use common::{APP_INFO, ENV_MUTEX};
+
+fn set_roots(root: &path::Path) {
+    common::set_xdg_roots(root);
+    // Make UserData alias UserConfig.
+    env::set_var("XDG_DATA_HOME", root.join("config"));
+}
+
+fn io_kind(err: &AppDirsError) -> Option<io::ErrorKind> {
+    match err {
+        AppDirsError::Io(e) => Some(e.kind()),
+        _ => None,
+    }
+}
+
+#[test_case(AppDataType::UserConfig, "work", "", "config/app-name/profiles/work"; "root")]
+#[test_case(AppDataType::UserData, "a/b", "c", "config/app-name/profiles/a,47,b/c"; "sanitized")]
+#[test_case(AppDataType::UserCache, "work", "x/y", "cache/app-name/profiles/work/x/y"; "subdir")]
+fn test_get_profile_dir(ty: AppDataType, profile: &str, subdir: &str, path: &str) {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_roots(dir.path());
+
+    assert_eq!(
+        dir.path().join(path),
+        app_dirs2::get_profile_dir(ty, &APP_INFO, profile, subdir).unwrap()
+    );
+}
+
+#[test]
+fn test_empty_profile() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_roots(dir.path());
+
+    let err = app_dirs2::get_profile_root(AppDataType::UserConfig, &APP_INFO, "").unwrap_err();
+    assert!(matches!(err, AppDirsError::InvalidAppInfo));
+}
+
+#[test]
+fn test_create_and_list() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_roots(dir.path());
+
+    assert!(app_dirs2::list_profiles(&APP_INFO).unwrap().is_empty());
+    app_dirs2::create_profile(&APP_INFO, "work").unwrap();
+    assert!(dir.path().join("config/app-name/profiles/work").is_dir());
+    assert!(dir.path().join("cache/app-name/profiles/work").is_dir());
+    app_dirs2::profile_dir(AppDataType::UserCache, &APP_INFO, "home", "thumbnails").unwrap();
+    assert_eq!(
+        vec!["home", "work"],
+        app_dirs2::list_profiles(&APP_INFO).unwrap()
+    );
+}
+
+#[test]
+fn test_rename() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_roots(dir.path());
+
+    app_dirs2::create_profile(&APP_INFO, "work").unwrap();
+    app_dirs2::create_profile(&APP_INFO, "home").unwrap();
+    let work = dir.path().join("config/app-name/profiles/work");
+    fs::write(work.join("settings.toml"), b"").unwrap();
+
+    let err = app_dirs2::rename_profile(&APP_INFO, "work", "home").unwrap_err();
+    assert_eq!(Some(io::ErrorKind::AlreadyExists), io_kind(&err));
+    assert!(work.is_dir());
+    let err = app_dirs2::rename_profile(&APP_INFO, "school", "play").unwrap_err();
+    assert_eq!(Some(io::ErrorKind::NotFound), io_kind(&err));
+
+    app_dirs2::rename_profile(&APP_INFO, "work", "office").unwrap();
+    assert!(dir
+        .path()
+        .join("config/app-name/profiles/office/settings.toml")
+        .is_file());
+    assert!(dir.path().join("cache/app-name/profiles/office").is_dir());
+    assert_eq!(
+        vec!["home", "office"],
+        app_dirs2::list_profiles(&APP_INFO).unwrap()
+    );
+}
+
+#[test]
+fn test_delete() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_roots(dir.path());
+
+    app_dirs2::create_profile(&APP_INFO, "work").unwrap();
+    app_dirs2::create_profile(&APP_INFO, "home").unwrap();
+    app_dirs2::delete_profile(&APP_INFO, "work").unwrap();
+    // Deleting a missing profile succeeds.
+    app_dirs2::delete_profile(&APP_INFO, "work").unwrap();
+    assert!(!dir.path().join("cache/app-name/profiles/work").exists());
+    assert_eq!(vec!["home"], app_dirs2::list_profiles(&APP_INFO).unwrap());
+}
@@ -0,0 +1,52 @@
+use app_dirs2::{AppDataType, AppInfo, AppRoots, OwnedAppInfo};
+use test_case::test_case;
+
+#[test_case(AppDataType::UserConfig, "\"user-config\""; "user config")]
+#[test_case(AppDataType::UserData, "\"user-data\""; "user data")]
+#[test_case(AppDataType::UserCache, "\"user-cache\""; "user cache")]
+#[test_case(AppDataType::SharedData, "\"shared-data\""; "shared data")]
+#[test_case(AppDataType::SharedConfig, "\"shared-config\""; "shared config")]
+fn test_data_type(ty: AppDataType, json: &str) {
+    assert_eq!(json, serde_json::to_string(&ty).unwrap());
+    assert_eq!(ty, serde_json::from_str::<AppDataType>(json).unwrap());
+}
+
+#[test]
+fn test_app_info() {
+    let info = AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    let json = serde_json::to_string(&info).unwrap();
+    assert_eq!(r#"{"name":"app-name","author":"app-author"}"#, json);
+
+    let owned: OwnedAppInfo = serde_json::from_str(&json).unwrap();
+    assert_eq!(OwnedAppInfo::new("app-name", "app-author"), owned);
+    assert_eq!(owned, info);
+    assert_eq!(json, serde_json::to_string(&owned).unwrap());
+}
+
+#[test]
+fn test_app_roots() {
+    let info = AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    let roots = AppRoots::resolve(&info).unwrap();
+    for &t in &[
+        AppDataType::UserConfig,
+        AppDataType::UserCache,
+        AppDataType::SharedData,
+    ] {
+        assert_eq!(
+            app_dirs2::get_app_root(t, &info).ok().as_deref(),
+            roots.get(t)
+        );
+    }
+
+    let json = serde_json::to_value(&roots).unwrap();
+    assert_eq!(roots.app, info);
+    assert_eq!("app-name", json["app"]["name"]);
+    assert!(json.get("user-config").is_some());
+    assert_eq!(roots, serde_json::from_value(json).unwrap());
+}
@@ -0,0 +1,111 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::{Duration, SystemTime};
+
+mod common;
+use common::{APP_INFO, ENV_MUTEX};
+
+/// Points `UserCache` below the given directory and returns the app's
+/// temporary directory.
+fn set_root(dir: &Path) -> PathBuf {
+    common::set_xdg_roots(dir);
+    dir.join("cache/app-name/tmp")
+}
+
+#[test]
+fn test_app_temp_dir() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let tmp = set_root(dir.path());
+
+    let scratch = app_dirs2::app_temp_dir(&APP_INFO).unwrap();
+    assert_eq!(Some(tmp.as_path()), scratch.path().parent());
+    let name = scratch.path().file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with(&format!("{}-", process::id())));
+    fs::write(scratch.path().join("part"), b"data").unwrap();
+    let path = scratch.path().to_owned();
+    drop(scratch);
+    assert!(!path.exists());
+
+    let kept = app_dirs2::app_temp_dir(&APP_INFO).unwrap().keep();
+    assert!(kept.is_dir());
+}
+
+#[test]
+fn test_app_temp_file() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let tmp = set_root(dir.path());
+
+    let mut file = app_dirs2::app_temp_file(&APP_INFO, ".json").unwrap();
+    assert_eq!(Some(tmp.as_path()), file.path().parent());
+    assert!(file.path().to_str().unwrap().ends_with(".json"));
+    file.as_file_mut().write_all(b"{}").unwrap();
+    file.as_file_mut().seek(SeekFrom::Start(0)).unwrap();
+    let mut contents = String::new();
+    file.as_file_mut().read_to_string(&mut contents).unwrap();
+    assert_eq!("{}", contents);
+    let path = file.path().to_owned();
+    drop(file);
+    assert!(!path.exists());
+}
+
+/// Backdates an entry past the minimum age for sweeping.
+fn age(path: &Path) {
+    let time = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+    fs::File::open(path).unwrap().set_modified(time).unwrap();
+}
+
+#[test]
+fn test_sweep_app_temp() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let tmp = set_root(dir.path());
+    assert_eq!(0, app_dirs2::sweep_app_temp(&APP_INFO).unwrap());
+
+    // Leftovers of a process that exited.
+    let mut child = Command::new("true").spawn().unwrap();
+    let dead = child.id();
+    child.wait().unwrap();
+    fs::create_dir_all(tmp.join(format!("{}-1/sub", dead))).unwrap();
+    fs::write(tmp.join(format!("{}-2.json", dead)), b"").unwrap();
+    // Leftovers of a process in another PID namespace whose id is in use.
+    fs::write(tmp.join(format!("{}-3", process::id())), b"").unwrap();
+    fs::write(tmp.join("notes"), b"").unwrap();
+    let live_dir = app_dirs2::app_temp_dir(&APP_INFO).unwrap();
+    let live_file = app_dirs2::app_temp_file(&APP_INFO, "").unwrap();
+    let kept = app_dirs2::app_temp_file(&APP_INFO, "").unwrap().keep();
+
+    // Too recent.
+    assert_eq!(0, app_dirs2::sweep_app_temp(&APP_INFO).unwrap());
+
+    for entry in fs::read_dir(&tmp).unwrap() {
+        age(&entry.unwrap().path());
+    }
+    assert_eq!(3, app_dirs2::sweep_app_temp(&APP_INFO).unwrap());
+    let mut remaining: Vec<_> = fs::read_dir(&tmp)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    remaining.sort();
+    let mut expected = vec![
+        live_dir.path().to_owned(),
+        live_file.path().to_owned(),
+        kept,
+        tmp.join("notes"),
+    ];
+    expected.sort();
+    assert_eq!(expected, remaining);
+    assert_eq!(0, app_dirs2::sweep_app_temp(&APP_INFO).unwrap());
+
+    let live_path = live_dir.path().to_owned();
+    drop(live_dir);
+    assert!(!live_path.exists());
+}
@@ -0,0 +1,103 @@
+use app_dirs2::{AppDataType, AppInfo, FakeHome, ResolutionSource};
+
+const APP_INFO: AppInfo = AppInfo {
+    name: "app-name",
+    author: "app-author",
+};
+
+// Only Windows puts the author into app directories.
+fn app_path(path: &str) -> String {
+    if cfg!(windows) {
+        format!("app-author/{}", path)
+    } else {
+        path.to_owned()
+    }
+}
+
+#[test]
+fn test_fake_home() {
+    let home = FakeHome::new().unwrap();
+    let path = home.path().to_owned();
+
+    for ty in AppDataType::all() {
+        assert_eq!(home.data_root(ty), app_dirs2::get_data_root(ty).unwrap());
+    }
+    let diagnosis = app_dirs2::diagnose(&APP_INFO);
+    assert!(diagnosis
+        .data_types
+        .iter()
+        .all(|d| d.source == Some(ResolutionSource::Override)));
+    home.assert_empty();
+
+    app_dirs2::app_dir(AppDataType::UserCache, &APP_INFO, "a/b").unwrap();
+    app_dirs2::app_root(AppDataType::UserConfig, &APP_INFO).unwrap();
+    std::fs::write(home.path().join("user-cache/file"), b"").unwrap();
+    home.assert_created_only(&[
+        format!("user-cache/{}", app_path("app-name/a/b")),
+        format!("user-config/{}", app_path("app-name")),
+    ]);
+
+    drop(home);
+    assert!(!path.exists());
+    assert_ne!(
+        path.join("user-config"),
+        app_dirs2::get_data_root(AppDataType::UserConfig).unwrap_or_default()
+    );
+}
+
+#[test]
+#[should_panic(expected = "unexpected directories")]
+fn test_assert_created_only() {
+    let home = FakeHome::new().unwrap();
+    app_dirs2::data_root(AppDataType::UserData).unwrap();
+    home.assert_created_only(&["user-config"]);
+}
+
+#[test]
+fn test_nested() {
+    let outer = FakeHome::new().unwrap();
+    {
+        let inner = FakeHome::new().unwrap();
+        assert_eq!(
+            inner.data_root(AppDataType::UserData),
+            app_dirs2::get_data_root(AppDataType::UserData).unwrap()
+        );
+    }
+    assert_eq!(
+        outer.data_root(AppDataType::UserData),
+        app_dirs2::get_data_root(AppDataType::UserData).unwrap()
+    );
+}
+
+#[test]
+fn test_other_thread() {
+    let home = FakeHome::new().unwrap();
+    let root = home.data_root(AppDataType::UserData);
+    std::thread::spawn(move || {
+        assert_ne!(
+            Some(root),
+            app_dirs2::get_data_root(AppDataType::UserData).ok()
+        );
+    })
+    .join()
+    .unwrap();
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_fake_home_async() {
+    let home = FakeHome::new().unwrap();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let app_dir = runtime
+        .block_on(app_dirs2::app_dir_async(
+            AppDataType::UserCache,
+            &APP_INFO,
+            "a",
+        ))
+        .unwrap();
+    assert!(app_dir.starts_with(home.path()));
+    home.assert_created_only(&[format!("user-cache/{}", app_path("app-name/a"))]);
+}
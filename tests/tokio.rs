@@ -0,0 +1,68 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::future::Future;
+use std::io;
+
+use app_dirs2::AppDataType;
+use test_case::test_case;
+
+mod common;
+use common::{set_xdg_roots, APP_INFO, ENV_MUTEX};
+
+// Runs the future on a fresh runtime, so the environment lock is not held
+// across an `.await`.
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test_case(AppDataType::UserCache; "user cache")]
+#[test_case(AppDataType::UserConfig; "user config")]
+#[test_case(AppDataType::UserData; "user data")]
+#[test_case(AppDataType::SharedConfig; "shared config")]
+#[test_case(AppDataType::SharedData; "shared data")]
+fn test_create(ty: AppDataType) -> io::Result<()> {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir()?;
+    set_xdg_roots(dir.path());
+
+    let data_root = block_on(app_dirs2::data_root_async(ty)).unwrap();
+    assert_eq!(app_dirs2::get_data_root(ty).unwrap(), data_root);
+    assert!(data_root.is_dir());
+
+    let app_root = block_on(app_dirs2::app_root_async(ty, &APP_INFO)).unwrap();
+    assert_eq!(app_dirs2::get_app_root(ty, &APP_INFO).unwrap(), app_root);
+    assert!(app_root.is_dir());
+
+    let app_dir = block_on(app_dirs2::app_dir_async(ty, &APP_INFO, "testdir")).unwrap();
+    assert_eq!(
+        app_dirs2::get_app_dir(ty, &APP_INFO, "testdir").unwrap(),
+        app_dir
+    );
+    assert!(app_dir.is_dir());
+
+    dir.close()
+}
+
+#[test]
+fn test_create_error() -> io::Result<()> {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir()?;
+    set_xdg_roots(dir.path());
+    // Make the config directory a regular file so nothing can be created in
+    // it.
+    std::fs::write(dir.path().join("config"), b"")?;
+
+    let err = block_on(app_dirs2::app_root_async(
+        AppDataType::UserConfig,
+        &APP_INFO,
+    ))
+    .unwrap_err();
+    assert!(matches!(err, app_dirs2::AppDirsError::Io(_)));
+
+    dir.close()
+}
@@ -0,0 +1,98 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use app_dirs2::AppDataType;
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Metadata, Subscriber};
+
+type Fields = BTreeMap<&'static str, String>;
+
+/// Records the fields of every event.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Fields>>>);
+
+impl Recorder {
+    fn events(&self) -> Vec<Fields> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.0.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn test_events() {
+    let dir = tempfile::tempdir().unwrap();
+    env::set_var("XDG_CONFIG_HOME", dir.path());
+
+    let app_info = app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    let recorder = Recorder::default();
+    let app_root = tracing::subscriber::with_default(recorder.clone(), || {
+        let app_root = app_dirs2::app_root(AppDataType::UserConfig, &app_info).unwrap();
+        app_dirs2::app_root(AppDataType::UserConfig, &app_info).unwrap();
+        app_root
+    });
+
+    let events = recorder.events();
+    let resolved: Vec<_> = events
+        .iter()
+        .filter(|e| e["message"] == "resolved data directory")
+        .collect();
+    assert_eq!(2, resolved.len());
+    assert_eq!("user-config", resolved[0]["data_type"]);
+    assert_eq!("xdg", resolved[0]["backend"]);
+    assert_eq!(
+        "environment variable XDG_CONFIG_HOME",
+        resolved[0]["source"]
+    );
+    assert_eq!(dir.path(), Path::new(&resolved[0]["path"]));
+
+    let created: Vec<_> = events
+        .iter()
+        .filter(|e| e["message"] == "ensured directory exists")
+        .map(|e| (e["path"].as_str(), e["created"].as_str()))
+        .collect();
+    let path = app_root.display().to_string();
+    assert_eq!(vec![(&*path, "true"), (&*path, "false")], created);
+}
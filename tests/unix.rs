@@ -5,7 +5,7 @@ use std::ffi;
 use std::path;
 use std::sync;
 
-use app_dirs2::AppDataType;
+use app_dirs2::{AppDataType, Sandbox};
 use once_cell::sync::Lazy;
 use test_case::test_case;
 
@@ -19,6 +19,13 @@ fn reset_env() {
     env::set_var("XDG_DATA_HOME", "");
     env::set_var("XDG_DATA_DIRS", "");
     env::set_var("XDG_CONFIG_DIRS", "");
+    env::remove_var("CONFIGURATION_DIRECTORY");
+    env::remove_var("STATE_DIRECTORY");
+    env::remove_var("CACHE_DIRECTORY");
+    env::remove_var("SNAP");
+    env::remove_var("SUDO_UID");
+    env::remove_var("SUDO_USER");
+    app_dirs2::set_unix_options(app_dirs2::UnixOptions::default());
 }
 
 #[test_case(AppDataType::UserCache, ".cache"; "user cache")]
@@ -110,3 +117,438 @@ fn test_home_and_xdg_dirs(ty: AppDataType, env_var: impl AsRef<ffi::OsStr>) {
     let app_dir = app_dirs2::get_app_dir(ty, &app_info, subdir).unwrap();
     assert_eq!(app_root.join(subdir), app_dir);
 }
+
+#[test]
+fn test_diagnose() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    reset_env();
+    env::set_var("HOME", dir.path());
+    env::set_var("XDG_CACHE_HOME", dir.path().join("cache"));
+
+    let app_info = app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    let diagnosis = app_dirs2::diagnose(&app_info);
+    assert_eq!(5, diagnosis.data_types.len());
+    let get = |ty| {
+        diagnosis
+            .data_types
+            .iter()
+            .find(|d| d.data_type == ty)
+            .unwrap()
+    };
+
+    let cache = get(AppDataType::UserCache);
+    assert_eq!(Some("xdg"), cache.backend);
+    assert_eq!(
+        Some(app_dirs2::ResolutionSource::EnvVar("XDG_CACHE_HOME")),
+        cache.source
+    );
+    assert!(!cache.used_defaults());
+    assert_eq!(
+        &dir.path().join("cache").join(app_info.name),
+        cache.app_root.as_ref().unwrap()
+    );
+    assert!(!cache.exists);
+    assert!(cache.writable);
+    assert!(cache.available_space.is_some());
+
+    let config = get(AppDataType::UserConfig);
+    assert!(config.used_defaults());
+    assert!(config
+        .env_vars
+        .contains(&("HOME", Some(dir.path().as_os_str().to_owned()))));
+    assert!(config
+        .env_vars
+        .contains(&("XDG_CONFIG_HOME", Some(ffi::OsString::new()))));
+
+    assert!(diagnosis.to_string().contains("user-cache:"));
+}
+
+#[test_case(AppDataType::UserCache, "CACHE_DIRECTORY"; "user cache")]
+#[test_case(AppDataType::UserConfig, "CONFIGURATION_DIRECTORY"; "user config")]
+#[test_case(AppDataType::UserData, "STATE_DIRECTORY"; "user data")]
+#[test_case(AppDataType::SharedConfig, "CONFIGURATION_DIRECTORY"; "shared config")]
+#[test_case(AppDataType::SharedData, "STATE_DIRECTORY"; "shared data")]
+fn test_systemd_dirs(ty: AppDataType, env_var: &str) {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let home_dir = tempfile::tempdir().unwrap();
+    let service_dir = tempfile::tempdir().unwrap();
+    reset_env();
+    env::set_var("HOME", home_dir.path());
+    let dirs = env::join_paths([service_dir.path(), path::Path::new("/var/lib/other")]).unwrap();
+    env::set_var(env_var, dirs);
+
+    // Ignored unless enabled
+    let data_root = app_dirs2::get_data_root(ty).unwrap();
+    assert!(!data_root.starts_with(service_dir.path()));
+
+    let mut options = app_dirs2::unix_options();
+    options.systemd_directories = true;
+    app_dirs2::set_unix_options(options);
+
+    let data_root = app_dirs2::get_data_root(ty).unwrap();
+    assert_eq!(service_dir.path(), data_root.as_path());
+
+    let app_info = app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    // The directory systemd created for the service is the app directory.
+    let app_root = app_dirs2::get_app_root(ty, &app_info).unwrap();
+    assert_eq!(data_root, app_root);
+    let app_dir = app_dirs2::get_app_dir(ty, &app_info, "sub").unwrap();
+    assert_eq!(data_root.join("sub"), app_dir);
+    let search_path = app_dirs2::get_app_search_path(ty, &app_info).unwrap();
+    assert_eq!(Some(&data_root), search_path.last());
+
+    reset_env();
+}
+
+#[test]
+fn test_detect_sandbox() {
+    let root = tempfile::tempdir().unwrap();
+    let no_env = |_: &str| None;
+    assert_eq!(None, app_dirs2::detect_sandbox_in(root.path(), no_env));
+
+    let snap_env = |var: &str| match var {
+        "SNAP" => Some("/snap/cool-app/12".into()),
+        "SNAP_NAME" => Some("cool-app".into()),
+        _ => None,
+    };
+    assert_eq!(
+        Some(Sandbox::Snap {
+            name: Some("cool-app".to_owned())
+        }),
+        app_dirs2::detect_sandbox_in(root.path(), snap_env)
+    );
+
+    std::fs::write(
+        root.path().join(".flatpak-info"),
+        "[Application]\nname=org.example.CoolApp\nruntime=runtime/org.gnome.Platform\n",
+    )
+    .unwrap();
+    assert_eq!(
+        Some(Sandbox::Flatpak {
+            app_id: Some("org.example.CoolApp".to_owned())
+        }),
+        app_dirs2::detect_sandbox_in(root.path(), no_env)
+    );
+
+    let empty_root = tempfile::tempdir().unwrap();
+    let flatpak_env = |var: &str| match var {
+        "FLATPAK_ID" => Some("org.example.Other".into()),
+        _ => None,
+    };
+    assert_eq!(
+        Some(Sandbox::Flatpak {
+            app_id: Some("org.example.Other".to_owned())
+        }),
+        app_dirs2::detect_sandbox_in(empty_root.path(), flatpak_env)
+    );
+}
+
+#[test_case(AppDataType::UserCache, "SNAP_USER_COMMON"; "user cache")]
+#[test_case(AppDataType::UserConfig, "SNAP_USER_DATA"; "user config")]
+#[test_case(AppDataType::UserData, "SNAP_USER_DATA"; "user data")]
+#[test_case(AppDataType::SharedConfig, "SNAP_DATA"; "shared config")]
+#[test_case(AppDataType::SharedData, "SNAP_DATA"; "shared data")]
+fn test_snap_dirs(ty: AppDataType, env_var: &str) {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let home_dir = tempfile::tempdir().unwrap();
+    let snap_dir = tempfile::tempdir().unwrap();
+    reset_env();
+    env::set_var("HOME", home_dir.path());
+    env::set_var(env_var, snap_dir.path());
+
+    // Only used inside Snap and if enabled
+    let mut options = app_dirs2::unix_options();
+    options.snap_directories = true;
+    app_dirs2::set_unix_options(options);
+    let data_root = app_dirs2::get_data_root(ty).unwrap();
+    assert!(!data_root.starts_with(snap_dir.path()));
+
+    env::set_var("SNAP", "/snap/cool-app/12");
+    let data_root = app_dirs2::get_data_root(ty).unwrap();
+    assert_eq!(snap_dir.path(), data_root.as_path());
+
+    env::remove_var(env_var);
+    reset_env();
+}
+
+#[test_case(AppDataType::UserCache, "/home/alice/.cache/app-name"; "user cache")]
+#[test_case(AppDataType::UserConfig, "/home/alice/.config/app-name"; "user config")]
+#[test_case(AppDataType::UserData, "/home/alice/.local/share/app-name"; "user data")]
+#[test_case(AppDataType::SharedConfig, "/etc/xdg/app-name"; "shared config")]
+#[test_case(AppDataType::SharedData, "/usr/local/share/app-name"; "shared data")]
+fn test_other_user(ty: AppDataType, expected: &str) {
+    let dir = tempfile::tempdir().unwrap();
+    let passwd = dir.path().join("passwd");
+    std::fs::write(
+        &passwd,
+        "# comment\nroot:x:0:0:root:/root:/bin/sh\nalice:x:1000:100:Alice:/home/alice:/bin/sh\n",
+    )
+    .unwrap();
+
+    let alice =
+        app_dirs2::UnixUser::lookup_in_file(app_dirs2::UserId::Name("alice"), &passwd).unwrap();
+    assert_eq!(1000, alice.uid);
+    assert_eq!(100, alice.gid);
+    assert_eq!(
+        alice,
+        app_dirs2::UnixUser::lookup_in_file(app_dirs2::UserId::Uid(1000), &passwd).unwrap()
+    );
+    assert!(app_dirs2::UnixUser::lookup_in_file(app_dirs2::UserId::Name("bob"), &passwd).is_err());
+
+    let app_info = app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    let app_root = app_dirs2::get_app_root_for_user(ty, &app_info, &alice).unwrap();
+    assert_eq!(path::Path::new(expected), app_root);
+}
+
+#[test_case(AppDataType::UserConfig, None; "user config")]
+#[test_case(AppDataType::UserCache, None; "user cache")]
+#[test_case(AppDataType::SharedConfig, Some("/etc/xdg"); "shared config")]
+fn test_other_user_without_home(ty: AppDataType, expected: Option<&str>) {
+    let user = app_dirs2::UnixUser {
+        name: "daemon".to_owned(),
+        uid: 1,
+        gid: 1,
+        home: path::PathBuf::new(),
+    };
+    let data_root = app_dirs2::get_data_root_for_user(ty, &user);
+    match expected {
+        Some(expected) => assert_eq!(path::Path::new(expected), data_root.unwrap()),
+        None => assert!(matches!(
+            data_root,
+            Err(app_dirs2::AppDirsError::NotSupported)
+        )),
+    }
+}
+
+#[test]
+fn test_lookup_root() {
+    let root = app_dirs2::UnixUser::lookup(app_dirs2::UserId::Uid(0)).unwrap();
+    assert_eq!(0, root.uid);
+    assert_eq!(
+        root,
+        app_dirs2::UnixUser::lookup(app_dirs2::UserId::Name(&root.name)).unwrap()
+    );
+}
+
+/// Returns `nobody` if the tests run as root, as `sudo` would leave them.
+fn sudo_invoker(dir: &path::Path) -> Option<app_dirs2::UnixUser> {
+    use std::os::unix::fs::MetadataExt;
+    if std::fs::metadata(dir).unwrap().uid() != 0 {
+        return None;
+    }
+    app_dirs2::UnixUser::lookup(app_dirs2::UserId::Name("nobody")).ok()
+}
+
+#[test_case(app_dirs2::SudoPolicy::Ignore; "ignore")]
+#[test_case(app_dirs2::SudoPolicy::EffectiveUser; "effective user")]
+#[test_case(app_dirs2::SudoPolicy::InvokingUser; "invoking user")]
+#[test_case(app_dirs2::SudoPolicy::Error; "error")]
+fn test_sudo_policy(policy: app_dirs2::SudoPolicy) {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let nobody = match sudo_invoker(dir.path()) {
+        Some(user) => user,
+        None => return,
+    };
+    let root = app_dirs2::UnixUser::lookup(app_dirs2::UserId::Uid(0)).unwrap();
+    reset_env();
+    env::set_var("HOME", dir.path());
+    env::set_var("SUDO_UID", nobody.uid.to_string());
+    let mut options = app_dirs2::unix_options();
+    options.sudo_policy = policy;
+    app_dirs2::set_unix_options(options);
+
+    let data_root = app_dirs2::get_data_root(AppDataType::UserConfig);
+    match policy {
+        app_dirs2::SudoPolicy::Ignore => {
+            assert_eq!(dir.path().join(".config"), data_root.unwrap())
+        },
+        app_dirs2::SudoPolicy::EffectiveUser => {
+            assert_eq!(root.home.join(".config"), data_root.unwrap())
+        },
+        app_dirs2::SudoPolicy::InvokingUser => {
+            assert_eq!(nobody.home.join(".config"), data_root.unwrap())
+        },
+        app_dirs2::SudoPolicy::Error => assert!(matches!(
+            data_root.unwrap_err(),
+            app_dirs2::AppDirsError::NotSupported
+        )),
+    }
+    // Shared types are not affected.
+    assert_eq!(
+        path::Path::new("/etc/xdg"),
+        app_dirs2::get_data_root(AppDataType::SharedConfig).unwrap()
+    );
+    reset_env();
+}
+
+#[test]
+fn test_chown_created_dirs() {
+    use std::os::unix::fs::MetadataExt;
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let nobody = match sudo_invoker(dir.path()) {
+        Some(user) => user,
+        None => return,
+    };
+    reset_env();
+    env::set_var("HOME", dir.path());
+    env::set_var("SUDO_USER", &nobody.name);
+    let mut options = app_dirs2::unix_options();
+    options.chown_created_dirs = true;
+    app_dirs2::set_unix_options(options);
+
+    let app_info = app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    let app_dir = app_dirs2::app_dir(AppDataType::UserData, &app_info, "sub").unwrap();
+    assert_eq!(dir.path().join(".local/share/app-name/sub"), app_dir);
+    for created in app_dir.ancestors().take_while(|p| *p != dir.path()) {
+        let metadata = std::fs::metadata(created).unwrap();
+        assert_eq!((nobody.uid, nobody.gid), (metadata.uid(), metadata.gid()));
+    }
+    assert_eq!(0, std::fs::metadata(dir.path()).unwrap().uid());
+
+    // A symlink in place of a directory that is about to be created is not
+    // followed.
+    std::os::unix::fs::symlink(dir.path().join("elsewhere"), dir.path().join(".cache")).unwrap();
+    assert!(app_dirs2::app_root(AppDataType::UserCache, &app_info).is_err());
+    assert!(!dir.path().join("elsewhere").exists());
+
+    // Shared directories stay with root.
+    env::set_var("XDG_DATA_DIRS", dir.path().join("shared"));
+    let shared = app_dirs2::app_root(AppDataType::SharedData, &app_info).unwrap();
+    assert_eq!(0, std::fs::metadata(&shared).unwrap().uid());
+    assert_eq!(
+        0,
+        std::fs::metadata(dir.path().join("shared")).unwrap().uid()
+    );
+    reset_env();
+}
+
+#[test]
+fn test_chown_created_dirs_symlinked_ancestor() {
+    use std::os::unix::fs::MetadataExt;
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let nobody = match sudo_invoker(dir.path()) {
+        Some(user) => user,
+        None => return,
+    };
+    reset_env();
+    env::set_var("HOME", dir.path());
+    env::set_var("SUDO_USER", &nobody.name);
+    let mut options = app_dirs2::unix_options();
+    options.chown_created_dirs = true;
+    app_dirs2::set_unix_options(options);
+
+    // Like a dotfile manager, which links `~/.config` to its repository.
+    std::fs::create_dir(dir.path().join("dotfiles-config")).unwrap();
+    std::os::unix::fs::symlink("dotfiles-config", dir.path().join(".config")).unwrap();
+
+    let app_info = app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    let app_root = app_dirs2::app_root(AppDataType::UserConfig, &app_info).unwrap();
+    assert_eq!(dir.path().join(".config/app-name"), app_root);
+    let metadata = std::fs::metadata(dir.path().join("dotfiles-config/app-name")).unwrap();
+    assert_eq!((nobody.uid, nobody.gid), (metadata.uid(), metadata.gid()));
+    let metadata = std::fs::metadata(dir.path().join("dotfiles-config")).unwrap();
+    assert_eq!(0, metadata.uid());
+    reset_env();
+}
+
+#[test_case(AppDataType::UserCache, ".cache"; "user cache")]
+#[test_case(AppDataType::UserConfig, ".config"; "user config")]
+#[test_case(AppDataType::UserData, ".local/share"; "user data")]
+fn test_passwd_home_fallback(ty: AppDataType, path: &str) {
+    use std::os::unix::fs::MetadataExt;
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let uid = std::fs::metadata(dir.path()).unwrap().uid();
+    let user = app_dirs2::UnixUser::lookup(app_dirs2::UserId::Uid(uid)).unwrap();
+    reset_env();
+
+    let resolved = app_dirs2::diagnose(&app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    });
+    let resolved = resolved
+        .data_types
+        .iter()
+        .find(|d| d.data_type == ty)
+        .unwrap();
+    assert_eq!(Some(app_dirs2::ResolutionSource::Passwd), resolved.source);
+    assert_eq!(user.home.join(path), app_dirs2::get_data_root(ty).unwrap());
+
+    env::remove_var("HOME");
+    assert_eq!(user.home.join(path), app_dirs2::get_data_root(ty).unwrap());
+
+    let mut options = app_dirs2::unix_options();
+    options.passwd_home_fallback = false;
+    app_dirs2::set_unix_options(options);
+    assert!(matches!(
+        app_dirs2::get_data_root(ty),
+        Err(app_dirs2::AppDirsError::NotSupported)
+    ));
+    let err = app_dirs2::get_data_root_with_context(ty).unwrap_err();
+    assert_eq!(Some("HOME"), err.context().env_var);
+    // The XDG variable still works without a home directory.
+    env::set_var("XDG_CACHE_HOME", dir.path());
+    env::set_var("XDG_CONFIG_HOME", dir.path());
+    env::set_var("XDG_DATA_HOME", dir.path());
+    assert_eq!(dir.path(), app_dirs2::get_data_root(ty).unwrap());
+    reset_env();
+}
+
+#[test]
+fn test_target_layout_matches() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    reset_env();
+    env::set_var("HOME", dir.path());
+    env::set_var("XDG_CONFIG_HOME", dir.path().join("config"));
+    env::set_var("XDG_DATA_DIRS", "relative:/xdg/data");
+
+    let mut dirs = app_dirs2::TargetDirs::default();
+    dirs.home = Some(dir.path().to_str().unwrap().to_owned());
+    dirs.xdg_config_home = Some(dir.path().join("config").to_str().unwrap().to_owned());
+    dirs.xdg_data_dirs = Some("relative:/xdg/data".into());
+    let app_info = app_dirs2::AppInfo {
+        name: "app-name",
+        author: "app-author",
+    };
+    assert_eq!(
+        Some(app_dirs2::TargetPlatform::Unix),
+        app_dirs2::TargetPlatform::current()
+    );
+    for ty in AppDataType::all() {
+        let target =
+            app_dirs2::target_app_root(app_dirs2::TargetPlatform::Unix, ty, &app_info, &dirs);
+        assert_eq!(
+            app_dirs2::get_app_root(ty, &app_info).unwrap(),
+            path::PathBuf::from(target.unwrap())
+        );
+    }
+    reset_env();
+}
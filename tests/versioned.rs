@@ -0,0 +1,75 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::fs;
+
+use app_dirs2::{AppDataType, VersionedAppInfo};
+use test_case::test_case;
+
+mod common;
+use common::{set_xdg_roots, APP_INFO, ENV_MUTEX};
+
+const BETA: VersionedAppInfo = VersionedAppInfo::new(APP_INFO, "3.x/beta");
+const CONFIG_ONLY: VersionedAppInfo = VersionedAppInfo {
+    app: APP_INFO,
+    version: "2.x",
+    scoped: &[AppDataType::UserConfig],
+};
+
+#[test_case(AppDataType::UserConfig, &BETA, "config/app-name/3.x,47,beta"; "scoped")]
+#[test_case(AppDataType::UserCache, &BETA, "cache/app-name"; "cache never scoped")]
+#[test_case(AppDataType::UserData, &CONFIG_ONLY, "data/app-name"; "not scoped")]
+fn test_versioned_app_root(ty: AppDataType, app: &VersionedAppInfo, path: &str) {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_xdg_roots(dir.path());
+
+    assert_eq!(
+        dir.path().join(path),
+        app_dirs2::get_versioned_app_root(ty, app).unwrap()
+    );
+    assert_eq!(
+        dir.path().join(path).join("a/b"),
+        app_dirs2::get_versioned_app_dir(ty, app, "a/b").unwrap()
+    );
+}
+
+#[test]
+fn test_empty_version() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_xdg_roots(dir.path());
+
+    let no_version = VersionedAppInfo::new(APP_INFO, "");
+    let err = app_dirs2::get_versioned_app_root(AppDataType::UserConfig, &no_version).unwrap_err();
+    assert!(matches!(err, app_dirs2::AppDirsError::InvalidAppInfo));
+}
+
+#[test]
+fn test_list_app_versions() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_xdg_roots(dir.path());
+
+    assert!(
+        app_dirs2::list_app_versions(AppDataType::UserConfig, &APP_INFO)
+            .unwrap()
+            .is_empty()
+    );
+    for version in &["2.10", "2.9", "10.0"] {
+        let app = VersionedAppInfo::new(APP_INFO, version);
+        let path = app_dirs2::versioned_app_dir(AppDataType::UserConfig, &app, "sub").unwrap();
+        assert!(path.is_dir());
+    }
+    app_dirs2::versioned_app_root(AppDataType::UserConfig, &BETA).unwrap();
+    let config = dir.path().join("config/app-name");
+    fs::write(config.join("settings.toml"), b"").unwrap();
+    // Profiles live next to the versions, but are not one.
+    app_dirs2::create_profile(&APP_INFO, "work").unwrap();
+    assert_eq!(
+        vec!["2.9", "2.10", "3.x,47,beta", "10.0"],
+        app_dirs2::list_app_versions(AppDataType::UserConfig, &APP_INFO).unwrap()
+    );
+}
@@ -0,0 +1,142 @@
+#![cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use app_dirs2::{AppDataType, AppDirWatcher};
+
+mod common;
+use common::{APP_INFO, ENV_MUTEX};
+
+/// Points `UserConfig` at `config` below the given directory and returns the
+/// (canonical) root.
+fn set_root(dir: &Path) -> PathBuf {
+    let root = dir.canonicalize().unwrap();
+    common::set_xdg_roots(&root);
+    root
+}
+
+fn watch_app_root() -> AppDirWatcher {
+    app_dirs2::watch_app_root(
+        AppDataType::UserConfig,
+        &APP_INFO,
+        Duration::from_millis(50),
+    )
+    .unwrap()
+}
+
+/// Waits for an event listing the given path.
+fn wait_for(watcher: &AppDirWatcher, path: &Path) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+        match watcher.recv_timeout(timeout) {
+            Some(event) if event.paths.iter().any(|p| p == path) => return,
+            Some(_) => {},
+            None => break,
+        }
+    }
+    panic!("no event for {}", path.display());
+}
+
+#[test]
+fn test_created_later() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = set_root(dir.path());
+    let app_root = root.join("config/app-name");
+
+    let watcher = watch_app_root();
+    assert_eq!(app_root, watcher.path());
+
+    // Created together with its parent.
+    app_dirs2::app_root(AppDataType::UserConfig, &APP_INFO).unwrap();
+    wait_for(&watcher, &app_root);
+    fs::write(app_root.join("settings"), b"a = 1").unwrap();
+    wait_for(&watcher, &app_root.join("settings"));
+}
+
+#[test]
+fn test_replaced() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = set_root(dir.path());
+    let app_root = app_dirs2::app_root(AppDataType::UserConfig, &APP_INFO).unwrap();
+
+    let watcher = watch_app_root();
+    // Replaced atomically.
+    let staging = root.join("config/app-name.new");
+    fs::create_dir(&staging).unwrap();
+    fs::remove_dir_all(&app_root).unwrap();
+    fs::rename(&staging, &app_root).unwrap();
+    wait_for(&watcher, &app_root);
+    fs::write(app_root.join("settings"), b"a = 2").unwrap();
+    wait_for(&watcher, &app_root.join("settings"));
+}
+
+#[test]
+fn test_recreated_repeatedly() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    set_root(dir.path());
+    let app_root = app_dirs2::app_root(AppDataType::UserConfig, &APP_INFO).unwrap();
+
+    let watcher = watch_app_root();
+    // Like an editor saving by replacing the directory, while the watcher
+    // is still setting up the watches after the previous replacement.
+    let deadline = Instant::now() + Duration::from_millis(500);
+    while Instant::now() < deadline {
+        fs::remove_dir_all(&app_root).unwrap();
+        fs::create_dir_all(app_root.join("a/b/c")).unwrap();
+        fs::write(app_root.join("a/b/file"), b"").unwrap();
+    }
+    wait_for(&watcher, &app_root);
+    while watcher.recv_timeout(Duration::from_millis(200)).is_some() {}
+
+    fs::write(app_root.join("settings"), b"a = 3").unwrap();
+    wait_for(&watcher, &app_root.join("settings"));
+}
+
+#[test]
+fn test_outside_changes() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = set_root(dir.path());
+    let app_root = app_dirs2::app_root(AppDataType::UserConfig, &APP_INFO).unwrap();
+
+    let watcher = watch_app_root();
+    fs::write(root.join("config/other"), b"").unwrap();
+    fs::write(app_root.join("last"), b"").unwrap();
+    wait_for(&watcher, &app_root.join("last"));
+    while let Some(event) = watcher.recv_timeout(Duration::from_millis(200)) {
+        assert!(
+            event.paths.iter().all(|p| p.starts_with(&app_root)),
+            "{:?}",
+            event
+        );
+    }
+}
+
+#[test]
+fn test_watch_app_dir() {
+    let _env_guard = ENV_MUTEX.lock();
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = set_root(dir.path());
+
+    let watcher = app_dirs2::watch_app_dir(
+        AppDataType::UserConfig,
+        &APP_INFO,
+        "conf.d",
+        Duration::from_millis(50),
+    )
+    .unwrap();
+    let conf_d = root.join("config/app-name/conf.d");
+    assert_eq!(conf_d, watcher.path());
+    app_dirs2::app_dir(AppDataType::UserConfig, &APP_INFO, "conf.d").unwrap();
+    wait_for(&watcher, &conf_d);
+}